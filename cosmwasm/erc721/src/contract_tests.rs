@@ -0,0 +1,219 @@
+use cosmwasm_schema::serde::de::DeserializeOwned;
+use cosmwasm_std::testing::{mock_dependencies, mock_env, MockApi, MockQuerier};
+use cosmwasm_std::{from_json, Addr, MemoryStorage, OwnedDeps};
+use cw_storage_plus::Map;
+use cw_utils::Expiration;
+
+use crate::error::ContractError;
+use crate::msg::{MigrateMsg, QueryMsg};
+use crate::query::{
+    AllNftInfoResponse, ApprovalResponse, ContractInfoResponse, MaxSupplyResponse, NftInfoResponse,
+    NumTokensResponse, OwnerOfResponse, TokensResponse,
+};
+use crate::state::{Approval, Erc721};
+use crate::{Extension, CONTRACT_NAME, CONTRACT_VERSION};
+
+const CREATOR: &str = "creator";
+const ALICE: &str = "alice";
+const BOB: &str = "bob";
+
+type MockDeps = OwnedDeps<MemoryStorage, MockApi, MockQuerier>;
+
+/// Writes a collection in the 0.1.0 layout: owners and approved spenders stored as plain
+/// strings, no contract or token info, and no contract version.
+fn setup_legacy_collection() -> MockDeps {
+    let mut deps = mock_dependencies();
+    cw_ownable::initialize_owner(&mut deps.storage, &deps.api, Some(CREATOR)).unwrap();
+
+    let token_owner: Map<u32, String> = Map::new("token_owner");
+    token_owner
+        .save(&mut deps.storage, 1, &ALICE.to_string())
+        .unwrap();
+    token_owner
+        .save(&mut deps.storage, 2, &ALICE.to_string())
+        .unwrap();
+    token_owner
+        .save(&mut deps.storage, 3, &BOB.to_string())
+        .unwrap();
+
+    let owned_tokens_count: Map<&Addr, u32> = Map::new("owned_tokens_count_key");
+    owned_tokens_count
+        .save(&mut deps.storage, &Addr::unchecked(ALICE), &2)
+        .unwrap();
+    owned_tokens_count
+        .save(&mut deps.storage, &Addr::unchecked(BOB), &1)
+        .unwrap();
+
+    // Approvals were first stored as the bare spender, then with an expiration
+    let token_approvals: Map<u32, String> = Map::new("token_approvals");
+    token_approvals
+        .save(&mut deps.storage, 1, &BOB.to_string())
+        .unwrap();
+    let token_approvals: Map<u32, Approval> = Map::new("token_approvals");
+    let approval = Approval {
+        spender: Addr::unchecked(ALICE),
+        expires: Expiration::AtHeight(1_000_000),
+    };
+    token_approvals
+        .save(&mut deps.storage, 3, &approval)
+        .unwrap();
+
+    deps
+}
+
+fn migrate_msg() -> MigrateMsg {
+    MigrateMsg {
+        name: Some("Collection".to_string()),
+        symbol: Some("COL".to_string()),
+    }
+}
+
+fn query<R: DeserializeOwned>(deps: &MockDeps, msg: QueryMsg) -> R {
+    let contract = Erc721::<Extension>::default();
+    from_json(contract.query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap()
+}
+
+#[test]
+fn migrate_converts_owners_and_approvals_to_addr() {
+    let mut deps = setup_legacy_collection();
+    let contract = Erc721::<Extension>::default();
+    contract
+        .migrate(deps.as_mut(), mock_env(), migrate_msg())
+        .unwrap();
+
+    let owner: OwnerOfResponse = query(&deps, QueryMsg::OwnerOf { token_id: 3 });
+    assert_eq!(owner.owner, BOB);
+
+    // The owner index is filled for the migrated owners
+    let tokens: TokensResponse = query(
+        &deps,
+        QueryMsg::Tokens {
+            owner: ALICE.to_string(),
+            start_after: None,
+            limit: None,
+        },
+    );
+    assert_eq!(tokens.tokens, vec![1, 2]);
+    let tokens: TokensResponse = query(
+        &deps,
+        QueryMsg::Tokens {
+            owner: BOB.to_string(),
+            start_after: None,
+            limit: None,
+        },
+    );
+    assert_eq!(tokens.tokens, vec![3]);
+
+    let approval: ApprovalResponse = query(
+        &deps,
+        QueryMsg::Approval {
+            token_id: 1,
+            include_expired: None,
+        },
+    );
+    assert_eq!(
+        approval.approval,
+        Some(Approval {
+            spender: Addr::unchecked(BOB),
+            expires: Expiration::Never {},
+        })
+    );
+    let approval: ApprovalResponse = query(
+        &deps,
+        QueryMsg::Approval {
+            token_id: 3,
+            include_expired: None,
+        },
+    );
+    assert_eq!(
+        approval.approval,
+        Some(Approval {
+            spender: Addr::unchecked(ALICE),
+            expires: Expiration::AtHeight(1_000_000),
+        })
+    );
+}
+
+#[test]
+fn migrate_fills_collection_and_token_info() {
+    let mut deps = setup_legacy_collection();
+    let contract = Erc721::<Extension>::default();
+    let resp = contract
+        .migrate(deps.as_mut(), mock_env(), migrate_msg())
+        .unwrap();
+    assert!(resp
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "from_version" && attr.value == "0.1.0"));
+
+    let version = cw2::get_contract_version(&deps.storage).unwrap();
+    assert_eq!(version.contract, CONTRACT_NAME);
+    assert_eq!(version.version, CONTRACT_VERSION);
+
+    let info: ContractInfoResponse = query(&deps, QueryMsg::ContractInfo {});
+    assert_eq!(info.name, "Collection");
+    assert_eq!(info.symbol, "COL");
+
+    let num_tokens: NumTokensResponse = query(&deps, QueryMsg::NumTokens {});
+    assert_eq!(num_tokens.count, 3);
+    let max_supply: MaxSupplyResponse = query(&deps, QueryMsg::MaxSupply {});
+    assert_eq!(max_supply.minted, 3);
+
+    let nft_info: NftInfoResponse<Extension> = query(&deps, QueryMsg::NftInfo { token_id: 2 });
+    assert_eq!(nft_info.token_uri, None);
+    assert_eq!(nft_info.extension, None);
+    let all_nft_info: AllNftInfoResponse<Extension> =
+        query(&deps, QueryMsg::AllNftInfo { token_id: 1 });
+    assert_eq!(all_nft_info.access.owner, ALICE);
+    assert_eq!(all_nft_info.info.token_uri, None);
+}
+
+#[test]
+fn migrate_requires_name_and_symbol_without_contract_info() {
+    let mut deps = setup_legacy_collection();
+    let contract = Erc721::<Extension>::default();
+    let msg = MigrateMsg {
+        name: Some("Collection".to_string()),
+        symbol: None,
+    };
+
+    let err = contract
+        .migrate(deps.as_mut(), mock_env(), msg)
+        .unwrap_err();
+    assert_eq!(err, ContractError::MissingContractInfo);
+}
+
+#[test]
+fn migrate_refuses_other_contract() {
+    let mut deps = setup_legacy_collection();
+    cw2::set_contract_version(&mut deps.storage, "crates.io:other", "0.1.0").unwrap();
+    let contract = Erc721::<Extension>::default();
+
+    let err = contract
+        .migrate(deps.as_mut(), mock_env(), migrate_msg())
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::CannotMigrate {
+            previous_contract: "crates.io:other".to_string(),
+        }
+    );
+}
+
+#[test]
+fn migrate_refuses_downgrade() {
+    let mut deps = setup_legacy_collection();
+    cw2::set_contract_version(&mut deps.storage, CONTRACT_NAME, "9.0.0").unwrap();
+    let contract = Erc721::<Extension>::default();
+
+    let err = contract
+        .migrate(deps.as_mut(), mock_env(), migrate_msg())
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::CannotDowngrade {
+            from: "9.0.0".to_string(),
+            to: CONTRACT_VERSION.to_string(),
+        }
+    );
+}
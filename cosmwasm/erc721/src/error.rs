@@ -15,4 +15,37 @@ pub enum ContractError {
 
     #[error("Approval not found for: {spender}")]
     ApprovalNotFound { spender: String },
+
+    #[error("Cannot set approval that is already expired")]
+    Expired,
+
+    #[error("Caller is not a minter")]
+    NotMinter,
+
+    #[error("Maximum supply reached")]
+    MaxSupplyReached,
+
+    #[error("token_id appears more than once in the batch")]
+    DuplicateTokenId,
+
+    #[error("no token_id left to assign")]
+    TokenIdOverflow,
+
+    #[error("Cannot migrate from a different contract: {previous_contract}")]
+    CannotMigrate { previous_contract: String },
+
+    #[error("Cannot migrate from newer version ({from}) to older ({to})")]
+    CannotDowngrade { from: String, to: String },
+
+    #[error("Semver parsing error: {0}")]
+    SemVer(String),
+
+    #[error("name and symbol are required to migrate a collection without contract info")]
+    MissingContractInfo,
+}
+
+impl From<semver::Error> for ContractError {
+    fn from(err: semver::Error) -> Self {
+        Self::SemVer(err.to_string())
+    }
 }
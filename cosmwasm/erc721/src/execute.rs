@@ -1,12 +1,23 @@
+use std::collections::BTreeMap;
+
 use cw_ownable::OwnershipError;
 
-use cosmwasm_std::{DepsMut, Env, MessageInfo, Response};
+use cosmwasm_schema::serde::{de::DeserializeOwned, Serialize};
+use cosmwasm_std::{
+    Addr, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdResult, Storage,
+};
+use cw_utils::Expiration;
 
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InstantiateMsg};
-use crate::state::Erc721;
+use crate::msg::{ExecuteMsg, InstantiateMsg, MintToken};
+use crate::receiver::Cw721ReceiveMsg;
+use crate::state::{Approval, ContractInfo, Erc721, TokenInfo};
+use crate::{CONTRACT_NAME, CONTRACT_VERSION};
 
-impl<'a> Erc721<'a> {
+impl<'a, T> Erc721<'a, T>
+where
+    T: Serialize + DeserializeOwned + Clone,
+{
     pub fn instantiate(
         &self,
         deps: DepsMut,
@@ -19,6 +30,17 @@ impl<'a> Erc721<'a> {
             None => info.sender,
         };
         cw_ownable::initialize_owner(deps.storage, deps.api, Some(owner.as_ref()))?;
+        cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+        let contract_info = ContractInfo {
+            name: msg.name,
+            symbol: msg.symbol,
+        };
+        self.contract_info.save(deps.storage, &contract_info)?;
+
+        if let Some(max_supply) = msg.max_supply {
+            self.max_supply.save(deps.storage, &max_supply)?;
+        }
 
         Ok(Response::default())
     }
@@ -28,17 +50,49 @@ impl<'a> Erc721<'a> {
         deps: DepsMut,
         env: Env,
         info: MessageInfo,
-        msg: ExecuteMsg,
+        msg: ExecuteMsg<T>,
     ) -> Result<Response, ContractError> {
         match msg {
-            ExecuteMsg::Mint { token_id, owner } => self.mint(deps, info, token_id, owner),
-            ExecuteMsg::Approve { spender, token_id } => {
-                self.approve(deps, env, info, spender, token_id)
+            ExecuteMsg::Mint {
+                token_id,
+                owner,
+                token_uri,
+                extension,
+            } => self.mint(deps, info, token_id, owner, token_uri, extension),
+            ExecuteMsg::MintNext {
+                owner,
+                token_uri,
+                extension,
+            } => self.mint_next(deps, info, owner, token_uri, extension),
+            ExecuteMsg::Approve {
+                spender,
+                token_id,
+                expires,
+            } => self.approve(deps, env, info, spender, token_id, expires),
+            ExecuteMsg::Revoke { spender, token_id } => {
+                self.revoke(deps, env, info, spender, token_id)
             }
+            ExecuteMsg::ApproveAll { operator, expires } => {
+                self.approve_all(deps, env, info, operator, expires)
+            }
+            ExecuteMsg::RevokeAll { operator } => self.revoke_all(deps, env, info, operator),
             ExecuteMsg::TransferNft {
                 recipient,
                 token_id,
             } => self.transfer_nft(deps, env, info, recipient, token_id),
+            ExecuteMsg::SendNft {
+                contract,
+                token_id,
+                msg,
+            } => self.send_nft(deps, env, info, contract, token_id, msg),
+            ExecuteMsg::BatchMint { tokens } => self.batch_mint(deps, info, tokens),
+            ExecuteMsg::BatchTransfer {
+                recipient,
+                token_ids,
+            } => self.batch_transfer(deps, env, info, recipient, token_ids),
+            ExecuteMsg::Burn { token_id } => self.burn(deps, env, info, token_id),
+            ExecuteMsg::AddMinter { minter } => self.add_minter(deps, info, minter),
+            ExecuteMsg::RemoveMinter { minter } => self.remove_minter(deps, info, minter),
             ExecuteMsg::UpdateOwnership(action) => Self::update_ownership(deps, env, info, action),
         }
     }
@@ -49,8 +103,10 @@ impl<'a> Erc721<'a> {
         info: MessageInfo,
         token_id: u32,
         owner: String,
+        token_uri: Option<String>,
+        extension: T,
     ) -> Result<Response, ContractError> {
-        cw_ownable::assert_owner(deps.storage, &info.sender)?;
+        self.assert_minter(deps.as_ref(), &info.sender)?;
 
         let token_owner = self.token_owner.may_load(deps.storage, token_id)?;
 
@@ -60,12 +116,20 @@ impl<'a> Erc721<'a> {
 
         let owner_addr = deps.api.addr_validate(&owner)?;
 
+        self.increment_minted(deps.storage, 1)?;
         self.owned_tokens_count
             .update(deps.storage, &owner_addr, |old| match old {
                 Some(x) => Ok::<u32, ContractError>(x + 1),
                 None => Ok(1),
             })?;
-        let _ = self.token_owner.save(deps.storage, token_id, &owner)?;
+        let _ = self.token_owner.save(deps.storage, token_id, &owner_addr)?;
+        self.increment_tokens(deps.storage)?;
+
+        let token_info = TokenInfo {
+            token_uri,
+            extension,
+        };
+        self.token_info.save(deps.storage, token_id, &token_info)?;
 
         Ok(Response::new()
             .add_attribute("action", "mint")
@@ -74,30 +138,152 @@ impl<'a> Erc721<'a> {
             .add_attribute("token_id", token_id.to_string()))
     }
 
+    fn mint_next(
+        &self,
+        deps: DepsMut,
+        info: MessageInfo,
+        owner: String,
+        token_uri: Option<String>,
+        extension: T,
+    ) -> Result<Response, ContractError> {
+        // Skip IDs that were already claimed through `Mint`
+        let mut token_id = self
+            .next_token_id
+            .may_load(deps.storage)?
+            .unwrap_or_default();
+        while self.token_owner.has(deps.storage, token_id) {
+            token_id = token_id
+                .checked_add(1)
+                .ok_or(ContractError::TokenIdOverflow)?;
+        }
+        let next_token_id = token_id
+            .checked_add(1)
+            .ok_or(ContractError::TokenIdOverflow)?;
+        self.next_token_id.save(deps.storage, &next_token_id)?;
+
+        self.mint(deps, info, token_id, owner, token_uri, extension)
+    }
+
+    fn batch_mint(
+        &self,
+        deps: DepsMut,
+        info: MessageInfo,
+        tokens: Vec<MintToken<T>>,
+    ) -> Result<Response, ContractError> {
+        self.assert_minter(deps.as_ref(), &info.sender)?;
+
+        // Validate every token before minting any of them
+        let token_ids: Vec<u32> = tokens.iter().map(|token| token.token_id).collect();
+        self.assert_unique(&token_ids)?;
+        let mut tokens_addr: Vec<(u32, Addr, TokenInfo<T>)> = Vec::with_capacity(tokens.len());
+        let mut owners: BTreeMap<Addr, u32> = BTreeMap::new();
+        for token in tokens {
+            if self.token_owner.has(deps.storage, token.token_id) {
+                return Err(ContractError::Claimed);
+            }
+            let owner_addr = deps.api.addr_validate(&token.owner)?;
+            *owners.entry(owner_addr.clone()).or_default() += 1;
+            let token_info = TokenInfo {
+                token_uri: token.token_uri,
+                extension: token.extension,
+            };
+            tokens_addr.push((token.token_id, owner_addr, token_info));
+        }
+
+        let amount = tokens_addr.len() as u64;
+        self.increment_minted(deps.storage, amount)?;
+        let token_count = self.token_count(deps.storage)? + amount;
+        self.token_count.save(deps.storage, &token_count)?;
+        for (owner_addr, count) in owners {
+            self.owned_tokens_count
+                .update(deps.storage, &owner_addr, |old| {
+                    Ok::<u32, ContractError>(old.unwrap_or_default() + count)
+                })?;
+        }
+
+        let mut resp = Response::new()
+            .add_attribute("action", "batch_mint")
+            .add_attribute("minter", info.sender);
+        for (token_id, owner_addr, token_info) in tokens_addr {
+            self.token_owner.save(deps.storage, token_id, &owner_addr)?;
+            self.token_info.save(deps.storage, token_id, &token_info)?;
+            resp = resp
+                .add_attribute("owner", owner_addr)
+                .add_attribute("token_id", token_id.to_string());
+        }
+
+        Ok(resp)
+    }
+
+    fn add_minter(
+        &self,
+        deps: DepsMut,
+        info: MessageInfo,
+        minter: String,
+    ) -> Result<Response, ContractError> {
+        cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+        let minter_addr = deps.api.addr_validate(&minter)?;
+        self.minters.save(deps.storage, &minter_addr, &Empty {})?;
+
+        Ok(Response::new()
+            .add_attribute("action", "add_minter")
+            .add_attribute("sender", info.sender)
+            .add_attribute("minter", minter))
+    }
+
+    fn remove_minter(
+        &self,
+        deps: DepsMut,
+        info: MessageInfo,
+        minter: String,
+    ) -> Result<Response, ContractError> {
+        cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+        let minter_addr = deps.api.addr_validate(&minter)?;
+        self.minters.remove(deps.storage, &minter_addr);
+
+        Ok(Response::new()
+            .add_attribute("action", "remove_minter")
+            .add_attribute("sender", info.sender)
+            .add_attribute("minter", minter))
+    }
+
     fn approve(
         &self,
         deps: DepsMut,
-        _env: Env,
+        env: Env,
         info: MessageInfo,
         spender: String,
         token_id: u32,
+        expires: Option<Expiration>,
     ) -> Result<Response, ContractError> {
         let caller = &info.sender;
         let owner = self.token_owner.may_load(deps.storage, token_id)?;
         match owner {
             Some(owner) => {
-                if deps.api.addr_validate(&owner)? != caller {
+                if owner != *caller {
                     return Err(ContractError::Ownership(OwnershipError::NotOwner));
                 }
             }
             None => return Err(ContractError::Ownership(OwnershipError::NoOwner)),
         }
 
-        let _spender_addr = deps.api.addr_validate(&spender)?;
+        let spender_addr = deps.api.addr_validate(&spender)?;
+
+        // Reject expired data as invalid
+        let expires = expires.unwrap_or_default();
+        if expires.is_expired(&env.block) {
+            return Err(ContractError::Expired);
+        }
 
+        let approval = Approval {
+            spender: spender_addr,
+            expires,
+        };
         let _ = self
             .token_approvals
-            .save(deps.storage, token_id, &spender)?;
+            .save(deps.storage, token_id, &approval)?;
 
         Ok(Response::new()
             .add_attribute("action", "approve")
@@ -106,30 +292,136 @@ impl<'a> Erc721<'a> {
             .add_attribute("token_id", token_id.to_string()))
     }
 
-    fn transfer_nft(
+    fn revoke(
         &self,
         deps: DepsMut,
         _env: Env,
         info: MessageInfo,
-        recipient: String,
+        spender: String,
         token_id: u32,
     ) -> Result<Response, ContractError> {
         let caller = &info.sender;
         let owner = self.token_owner.may_load(deps.storage, token_id)?;
-        let owner_addr = match owner {
-            Some(owner) => deps.api.addr_validate(&owner)?,
+        match owner {
+            Some(owner) => {
+                if owner != *caller {
+                    return Err(ContractError::Ownership(OwnershipError::NotOwner));
+                }
+            }
             None => return Err(ContractError::Ownership(OwnershipError::NoOwner)),
+        }
+
+        let spender_addr = deps.api.addr_validate(&spender)?;
+        let approval = self.token_approvals.may_load(deps.storage, token_id)?;
+        if !matches!(approval, Some(approval) if approval.spender == spender_addr) {
+            return Err(ContractError::ApprovalNotFound { spender });
+        }
+        self.token_approvals.remove(deps.storage, token_id);
+
+        Ok(Response::new()
+            .add_attribute("action", "revoke")
+            .add_attribute("sender", &info.sender)
+            .add_attribute("spender", &spender)
+            .add_attribute("token_id", token_id.to_string()))
+    }
+
+    fn approve_all(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        operator: String,
+        expires: Option<Expiration>,
+    ) -> Result<Response, ContractError> {
+        // Reject expired data as invalid
+        let expires = expires.unwrap_or_default();
+        if expires.is_expired(&env.block) {
+            return Err(ContractError::Expired);
+        }
+
+        let operator_addr = deps.api.addr_validate(&operator)?;
+        self.operators
+            .save(deps.storage, (&info.sender, &operator_addr), &expires)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "approve_all")
+            .add_attribute("sender", &info.sender)
+            .add_attribute("operator", &operator))
+    }
+
+    fn revoke_all(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        operator: String,
+    ) -> Result<Response, ContractError> {
+        let operator_addr = deps.api.addr_validate(&operator)?;
+        self.operators
+            .remove(deps.storage, (&info.sender, &operator_addr));
+
+        Ok(Response::new()
+            .add_attribute("action", "revoke_all")
+            .add_attribute("sender", &info.sender)
+            .add_attribute("operator", &operator))
+    }
+
+    fn transfer_nft(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        recipient: String,
+        token_id: u32,
+    ) -> Result<Response, ContractError> {
+        self.transfer_token(deps, &env, &info, &recipient, token_id)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "transfer_nft")
+            .add_attribute("sender", info.sender)
+            .add_attribute("recipient", recipient)
+            .add_attribute("token_id", token_id.to_string()))
+    }
+
+    fn send_nft(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        contract: String,
+        token_id: u32,
+        msg: Binary,
+    ) -> Result<Response, ContractError> {
+        self.transfer_token(deps, &env, &info, &contract, token_id)?;
+
+        // Notify the receiving contract of the transfer
+        let receive_msg = Cw721ReceiveMsg {
+            sender: info.sender.to_string(),
+            token_id,
+            msg,
         };
-        if !(owner_addr == caller
-            || self.token_approvals.may_load(deps.storage, token_id)? == Some(caller.to_string()))
-        {
-            return Err(ContractError::ApprovalNotFound {
-                spender: caller.to_string(),
-            });
-        };
+
+        Ok(Response::new()
+            .add_message(receive_msg.into_cosmos_msg(contract.to_owned())?)
+            .add_attribute("action", "send_nft")
+            .add_attribute("sender", info.sender)
+            .add_attribute("recipient", contract)
+            .add_attribute("token_id", token_id.to_string()))
+    }
+
+    /// Moves token `token_id` to `recipient` if the sender is allowed to transfer it.
+    fn transfer_token(
+        &self,
+        deps: DepsMut,
+        env: &Env,
+        info: &MessageInfo,
+        recipient: &str,
+        token_id: u32,
+    ) -> Result<(), ContractError> {
+        let owner_addr = self.check_can_send(deps.as_ref(), env, token_id, &info.sender)?;
 
         // Check recipient is valid address.
-        let recipient_addr = deps.api.addr_validate(&recipient)?;
+        let recipient_addr = deps.api.addr_validate(recipient)?;
 
         self.token_approvals.remove(deps.storage, token_id);
         let _ = self
@@ -140,22 +432,171 @@ impl<'a> Erc721<'a> {
                     msg: "Should not be possible".to_string(),
                 })),
             });
-        self.token_owner.remove(deps.storage, token_id);
+        self.token_owner.remove(deps.storage, token_id)?;
 
-        let _ = self.token_owner.save(deps.storage, token_id, &recipient)?;
+        let _ = self
+            .token_owner
+            .save(deps.storage, token_id, &recipient_addr)?;
         self.owned_tokens_count
             .update(deps.storage, &recipient_addr, |old| match old {
                 Some(x) => Ok::<u32, ContractError>(x + 1),
                 None => Ok(1),
             })?;
 
+        Ok(())
+    }
+
+    fn batch_transfer(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        recipient: String,
+        token_ids: Vec<u32>,
+    ) -> Result<Response, ContractError> {
+        // Validate every token before transferring any of them
+        self.assert_unique(&token_ids)?;
+        let recipient_addr = deps.api.addr_validate(&recipient)?;
+        let mut owners: BTreeMap<Addr, u32> = BTreeMap::new();
+        for token_id in &token_ids {
+            let owner_addr = self.check_can_send(deps.as_ref(), &env, *token_id, &info.sender)?;
+            *owners.entry(owner_addr).or_default() += 1;
+        }
+
+        for (owner_addr, count) in owners {
+            self.owned_tokens_count
+                .update(deps.storage, &owner_addr, |old| match old {
+                    Some(x) => Ok(x - count),
+                    None => Err(ContractError::Std(cosmwasm_std::StdError::GenericErr {
+                        msg: "Should not be possible".to_string(),
+                    })),
+                })?;
+        }
+        self.owned_tokens_count
+            .update(deps.storage, &recipient_addr, |old| {
+                Ok::<u32, ContractError>(old.unwrap_or_default() + token_ids.len() as u32)
+            })?;
+
+        let mut resp = Response::new()
+            .add_attribute("action", "batch_transfer")
+            .add_attribute("sender", info.sender)
+            .add_attribute("recipient", &recipient);
+        for token_id in token_ids {
+            self.token_approvals.remove(deps.storage, token_id);
+            self.token_owner
+                .save(deps.storage, token_id, &recipient_addr)?;
+            resp = resp.add_attribute("token_id", token_id.to_string());
+        }
+
+        Ok(resp)
+    }
+
+    fn burn(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: u32,
+    ) -> Result<Response, ContractError> {
+        let owner_addr = self.check_can_send(deps.as_ref(), &env, token_id, &info.sender)?;
+
+        self.token_approvals.remove(deps.storage, token_id);
+        self.token_info.remove(deps.storage, token_id);
+        self.token_owner.remove(deps.storage, token_id)?;
+        self.decrement_tokens(deps.storage)?;
+        self.owned_tokens_count
+            .update(deps.storage, &owner_addr, |old| match old {
+                Some(x) => Ok(x - 1),
+                None => Err(ContractError::Std(cosmwasm_std::StdError::GenericErr {
+                    msg: "Should not be possible".to_string(),
+                })),
+            })?;
+
         Ok(Response::new()
-            .add_attribute("action", "transfer_nft")
+            .add_attribute("action", "burn")
             .add_attribute("sender", info.sender)
-            .add_attribute("recipient", recipient)
+            .add_attribute("owner", owner_addr)
             .add_attribute("token_id", token_id.to_string()))
     }
 
+    /// Checks that no token ID appears twice in a batch.
+    fn assert_unique(&self, token_ids: &[u32]) -> Result<(), ContractError> {
+        let mut sorted = token_ids.to_vec();
+        sorted.sort_unstable();
+        if sorted.windows(2).any(|pair| pair[0] == pair[1]) {
+            return Err(ContractError::DuplicateTokenId);
+        }
+
+        Ok(())
+    }
+
+    /// Checks that `sender` is the contract owner or one of the minters.
+    fn assert_minter(&self, deps: Deps, sender: &Addr) -> Result<(), ContractError> {
+        if cw_ownable::assert_owner(deps.storage, sender).is_ok()
+            || self.minters.has(deps.storage, sender)
+        {
+            return Ok(());
+        }
+
+        Err(ContractError::NotMinter)
+    }
+
+    /// Counts `amount` newly minted tokens, failing if they exceed the maximum supply.
+    fn increment_minted(
+        &self,
+        storage: &mut dyn Storage,
+        amount: u64,
+    ) -> Result<u64, ContractError> {
+        let minted = self.minted_count.may_load(storage)?.unwrap_or_default() + amount;
+        if let Some(max_supply) = self.max_supply.may_load(storage)? {
+            if minted > max_supply {
+                return Err(ContractError::MaxSupplyReached);
+            }
+        }
+
+        self.minted_count.save(storage, &minted)?;
+        Ok(minted)
+    }
+
+    /// Returns the owner of `token_id` if `caller` is the owner, an approved spender or an operator.
+    fn check_can_send(
+        &self,
+        deps: Deps,
+        env: &Env,
+        token_id: u32,
+        caller: &Addr,
+    ) -> Result<Addr, ContractError> {
+        let owner_addr = match self.token_owner.may_load(deps.storage, token_id)? {
+            Some(owner) => owner,
+            None => return Err(ContractError::Ownership(OwnershipError::NoOwner)),
+        };
+        if !(owner_addr == *caller
+            || self.is_approved(deps, env, token_id, caller)?
+            || self.is_operator(deps, env, &owner_addr, caller)?)
+        {
+            return Err(ContractError::ApprovalNotFound {
+                spender: caller.to_string(),
+            });
+        };
+
+        Ok(owner_addr)
+    }
+
+    /// Returns true if `spender` holds an unexpired Approve for `token_id`.
+    fn is_approved(&self, deps: Deps, env: &Env, token_id: u32, spender: &Addr) -> StdResult<bool> {
+        let approval = self.token_approvals.may_load(deps.storage, token_id)?;
+
+        Ok(matches!(approval, Some(approval)
+            if approval.spender == *spender && !approval.expires.is_expired(&env.block)))
+    }
+
+    /// Returns true if `operator` holds an unexpired ApproveAll from `owner`.
+    fn is_operator(&self, deps: Deps, env: &Env, owner: &Addr, operator: &Addr) -> StdResult<bool> {
+        let expires = self.operators.may_load(deps.storage, (owner, operator))?;
+
+        Ok(matches!(expires, Some(expires) if !expires.is_expired(&env.block)))
+    }
+
     fn update_ownership(
         deps: DepsMut,
         env: Env,
@@ -1,16 +1,36 @@
 pub mod error;
 mod execute;
+mod migrate;
 pub mod msg;
 mod query;
+pub mod receiver;
 pub mod state;
 
+#[cfg(test)]
+mod contract_tests;
+
 pub use crate::error::ContractError;
-pub use crate::msg::{ExecuteMsg, InstantiateMsg, MinterResponse, QueryMsg};
-pub use crate::query::{ApprovalResponse, OwnerOfResponse};
+pub use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, MintToken, MinterResponse, QueryMsg};
+pub use crate::query::{
+    AllNftInfoResponse, ApprovalResponse, ContractInfoResponse, MaxSupplyResponse, MintersResponse,
+    NftInfoResponse, NumTokensResponse, OperatorResponse, OperatorsResponse, OwnerOfResponse,
+    TokensResponse,
+};
+pub use crate::receiver::Cw721ReceiveMsg;
+pub use crate::state::{Approval, TokenInfo};
 pub use cw_utils::Expiration;
 
 pub use cw_ownable::{Action, Ownership, OwnershipError};
 
+use cosmwasm_std::Empty;
+
+/// Default metadata extension, used when the contract is deployed on its own.
+pub type Extension = Option<Empty>;
+
+// Version info for migration
+pub const CONTRACT_NAME: &str = "crates.io:erc721";
+pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
 pub mod entry {
     use self::state::Erc721;
 
@@ -27,7 +47,7 @@ pub mod entry {
         info: MessageInfo,
         msg: InstantiateMsg,
     ) -> Result<Response, ContractError> {
-        let contract = Erc721::default();
+        let contract = Erc721::<Extension>::default();
         contract.instantiate(deps, env, info, msg)
     }
 
@@ -36,15 +56,21 @@ pub mod entry {
         deps: DepsMut,
         env: Env,
         info: MessageInfo,
-        msg: ExecuteMsg,
+        msg: ExecuteMsg<Extension>,
     ) -> Result<Response, ContractError> {
-        let contract = Erc721::default();
+        let contract = Erc721::<Extension>::default();
         contract.execute(deps, env, info, msg)
     }
 
     #[cfg_attr(not(feature = "library"), entry_point)]
     pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
-        let contract = Erc721::default();
+        let contract = Erc721::<Extension>::default();
         contract.query(deps, env, msg)
     }
+
+    #[cfg_attr(not(feature = "library"), entry_point)]
+    pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+        let contract = Erc721::<Extension>::default();
+        contract.migrate(deps, env, msg)
+    }
 }
@@ -0,0 +1,137 @@
+use cosmwasm_schema::serde::{de::DeserializeOwned, Deserialize, Serialize};
+use cosmwasm_std::{DepsMut, Env, Order, Response, StdResult};
+use cw_storage_plus::Map;
+use cw_utils::Expiration;
+use semver::Version;
+
+use crate::error::ContractError;
+use crate::msg::MigrateMsg;
+use crate::state::{Approval, ContractInfo, Erc721, TokenId, TokenInfo};
+use crate::{CONTRACT_NAME, CONTRACT_VERSION};
+
+/// Approvals as stored before owners and spenders were kept as `Addr`: either the bare
+/// spender address, or an `Approval` holding the spender as an unchecked string.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(crate = "cosmwasm_schema::serde", untagged)]
+enum LegacyApproval {
+    Spender(String),
+    Approval {
+        spender: String,
+        expires: Expiration,
+    },
+}
+
+/// Legacy view of the approvals stored under the default `token_approvals` namespace.
+const LEGACY_TOKEN_APPROVALS: Map<TokenId, LegacyApproval> = Map::new("token_approvals");
+
+impl<'a, T> Erc721<'a, T>
+where
+    T: Serialize + DeserializeOwned + Clone + Default,
+{
+    pub fn migrate(
+        &self,
+        mut deps: DepsMut,
+        _env: Env,
+        msg: MigrateMsg,
+    ) -> Result<Response, ContractError> {
+        // Contracts instantiated before versioning was added have no stored version
+        let from_version = match cw2::get_contract_version(deps.storage) {
+            Ok(stored) => {
+                if stored.contract != CONTRACT_NAME {
+                    return Err(ContractError::CannotMigrate {
+                        previous_contract: stored.contract,
+                    });
+                }
+                stored.version.parse::<Version>()?
+            }
+            Err(_) => Version::new(0, 1, 0),
+        };
+        let to_version = CONTRACT_VERSION.parse::<Version>()?;
+        if from_version > to_version {
+            return Err(ContractError::CannotDowngrade {
+                from: from_version.to_string(),
+                to: to_version.to_string(),
+            });
+        }
+
+        // Run the state migration of every version between the stored one and this one
+        if from_version < Version::new(0, 2, 0) {
+            self.migrate_addr_state(deps.branch())?;
+            self.migrate_collection_info(deps.branch(), msg)?;
+        }
+
+        cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "migrate")
+            .add_attribute("from_version", from_version.to_string())
+            .add_attribute("to_version", to_version.to_string()))
+    }
+
+    /// Re-saves token owners and approval spenders stored as strings as validated `Addr`s.
+    ///
+    /// Saving every owner again also fills the owner index and the token count, which did
+    /// not exist in the first storage layout.
+    fn migrate_addr_state(&self, deps: DepsMut) -> StdResult<()> {
+        let owners = self
+            .token_owner
+            .range(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+        let token_count = owners.len() as u64;
+        for (token_id, owner) in owners {
+            let owner_addr = deps.api.addr_validate(owner.as_str())?;
+            self.token_owner.save(deps.storage, token_id, &owner_addr)?;
+        }
+        self.token_count.save(deps.storage, &token_count)?;
+        if self.minted_count.may_load(deps.storage)?.is_none() {
+            self.minted_count.save(deps.storage, &token_count)?;
+        }
+
+        let approvals = LEGACY_TOKEN_APPROVALS
+            .range(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+        for (token_id, approval) in approvals {
+            let (spender, expires) = match approval {
+                LegacyApproval::Spender(spender) => (spender, Expiration::Never {}),
+                LegacyApproval::Approval { spender, expires } => (spender, expires),
+            };
+            let approval = Approval {
+                spender: deps.api.addr_validate(&spender)?,
+                expires,
+            };
+            self.token_approvals
+                .save(deps.storage, token_id, &approval)?;
+        }
+
+        Ok(())
+    }
+
+    /// Saves the collection name and symbol and a default `TokenInfo` for every token,
+    /// none of which were stored in the first storage layout.
+    fn migrate_collection_info(&self, deps: DepsMut, msg: MigrateMsg) -> Result<(), ContractError> {
+        if self.contract_info.may_load(deps.storage)?.is_none() {
+            let (name, symbol) = match (msg.name, msg.symbol) {
+                (Some(name), Some(symbol)) => (name, symbol),
+                _ => return Err(ContractError::MissingContractInfo),
+            };
+            self.contract_info
+                .save(deps.storage, &ContractInfo { name, symbol })?;
+        }
+
+        let token_ids = self
+            .token_owner
+            .keys(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+        for token_id in token_ids {
+            if !self.token_info.has(deps.storage, token_id) {
+                let info = TokenInfo {
+                    token_uri: None,
+                    extension: T::default(),
+                };
+                self.token_info.save(deps.storage, token_id, &info)?;
+            }
+        }
+
+        Ok(())
+    }
+}
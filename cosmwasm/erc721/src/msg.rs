@@ -1,7 +1,14 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::Binary;
 use cw_ownable::cw_ownable_execute;
+use cw_utils::Expiration;
 
-use crate::query::{ApprovalResponse, BalanceOfResponse, OwnerOfResponse};
+use crate::query::{
+    AllNftInfoResponse, ApprovalResponse, BalanceOfResponse, ContractInfoResponse,
+    MaxSupplyResponse, MintersResponse, NftInfoResponse, NumTokensResponse, OperatorResponse,
+    OperatorsResponse, OwnerOfResponse, TokensResponse,
+};
+use crate::Extension;
 
 #[cw_serde]
 pub struct InstantiateMsg {
@@ -11,17 +18,43 @@ pub struct InstantiateMsg {
     pub symbol: String,
 
     pub minter: Option<String>,
+
+    /// Maximum number of tokens that can be minted, unlimited if unset
+    pub max_supply: Option<u64>,
 }
 
 #[cw_ownable_execute]
 #[cw_serde]
-pub enum ExecuteMsg {
+pub enum ExecuteMsg<T> {
     /// Transfer is a base message to move a token to another account
     TransferNft { recipient: String, token_id: u32 },
+    /// Send is a base message to transfer a token to a contract and trigger an action
+    /// on the receiving contract.
+    SendNft {
+        contract: String,
+        token_id: u32,
+        msg: Binary,
+    },
 
     /// Allows spender to transfer the token from the owner's account.
     /// If expiration is set, then this allowance has a time/height limit
-    Approve { spender: String, token_id: u32 },
+    Approve {
+        spender: String,
+        token_id: u32,
+        expires: Option<Expiration>,
+    },
+
+    /// Remove previously granted Approve permission
+    Revoke { spender: String, token_id: u32 },
+
+    /// Allows operator to transfer any token owned by the sender.
+    /// If expiration is set, then this allowance has a time/height limit
+    ApproveAll {
+        operator: String,
+        expires: Option<Expiration>,
+    },
+    /// Remove a previously granted ApproveAll permission
+    RevokeAll { operator: String },
 
     /// Mint a new NFT, can only be called by the contract minter
     Mint {
@@ -29,7 +62,56 @@ pub enum ExecuteMsg {
         token_id: u32,
         /// The owner of the newly minter NFT
         owner: String,
+        /// Universal resource identifier for this NFT
+        token_uri: Option<String>,
+        /// Any custom extension used by this contract
+        extension: T,
     },
+
+    /// Mint a new NFT with the next free token ID, can only be called by the contract minter
+    MintNext {
+        /// The owner of the newly minter NFT
+        owner: String,
+        /// Universal resource identifier for this NFT
+        token_uri: Option<String>,
+        /// Any custom extension used by this contract
+        extension: T,
+    },
+
+    /// Mint several NFTs at once, can only be called by the contract minter.
+    /// Fails without minting anything if any of the tokens cannot be minted
+    BatchMint {
+        /// ID, owner and metadata of each new NFT
+        tokens: Vec<MintToken<T>>,
+    },
+
+    /// Transfer several tokens to another account at once.
+    /// Fails without transferring anything if any of the tokens cannot be transferred
+    BatchTransfer {
+        recipient: String,
+        token_ids: Vec<u32>,
+    },
+
+    /// Burn an NFT the sender has access to
+    Burn { token_id: u32 },
+
+    /// Allow an additional account to mint, can only be called by the contract owner
+    AddMinter { minter: String },
+    /// Remove a previously added minter, can only be called by the contract owner
+    RemoveMinter { minter: String },
+}
+
+/// An NFT minted by `BatchMint`
+#[cw_serde]
+pub struct MintToken<T> {
+    /// Unique ID of the NFT
+    pub token_id: u32,
+    /// The owner of the newly minted NFT
+    pub owner: String,
+    /// Universal resource identifier for this NFT
+    pub token_uri: Option<String>,
+    /// Any custom extension used by this contract
+    pub extension: T,
 }
 
 #[cw_serde]
@@ -41,13 +123,70 @@ pub enum QueryMsg {
     /// Return the owner of the given token, error if token does not exist
     #[returns(OwnerOfResponse)]
     OwnerOf { token_id: u32 },
+    /// Return the metadata of the given token, error if token does not exist
+    #[returns(NftInfoResponse<Extension>)]
+    NftInfo { token_id: u32 },
+    /// Return the owner, approval and metadata of the given token in a single call
+    #[returns(AllNftInfoResponse<Extension>)]
+    AllNftInfo { token_id: u32 },
     /// Return spender that can access all of the owner's tokens.
     #[returns(ApprovalResponse)]
-    Approval { token_id: u32 },
+    Approval {
+        token_id: u32,
+        /// unset or false will filter out expired approvals, you must set to true to see them
+        include_expired: Option<bool>,
+    },
+    /// Return the operator approval of `operator` on all tokens of `owner`, error if not set
+    #[returns(OperatorResponse)]
+    Operator {
+        owner: String,
+        operator: String,
+        /// unset or false will filter out expired approvals, you must set to true to see them
+        include_expired: Option<bool>,
+    },
+    /// List all operators that can access all of the owner's tokens
+    #[returns(OperatorsResponse)]
+    AllOperators {
+        owner: String,
+        /// unset or false will filter out expired items, you must set to true to see them
+        include_expired: Option<bool>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Return the name and symbol of the collection
+    #[returns(ContractInfoResponse)]
+    ContractInfo {},
+
+    /// Return the total number of tokens issued
+    #[returns(NumTokensResponse)]
+    NumTokens {},
+    /// Return all tokens owned by the given address, paginated by token ID
+    #[returns(TokensResponse)]
+    Tokens {
+        owner: String,
+        start_after: Option<u32>,
+        limit: Option<u32>,
+    },
+    /// Return all tokens controlled by the contract, paginated by token ID
+    #[returns(TokensResponse)]
+    AllTokens {
+        start_after: Option<u32>,
+        limit: Option<u32>,
+    },
 
     /// Return the minter
     #[returns(MinterResponse)]
     Minter {},
+    /// List the accounts allowed to mint besides the contract owner
+    #[returns(MintersResponse)]
+    Minters {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Return the maximum supply and the number of tokens minted so far
+    #[returns(MaxSupplyResponse)]
+    MaxSupply {},
 }
 
 /// Shows who can mint these tokens
@@ -55,3 +194,11 @@ pub enum QueryMsg {
 pub struct MinterResponse {
     pub minter: Option<String>,
 }
+
+#[cw_serde]
+pub struct MigrateMsg {
+    /// Name of the NFT collection, required to migrate from 0.1.0, which did not store it
+    pub name: Option<String>,
+    /// Symbol of the NFT collection, required to migrate from 0.1.0
+    pub symbol: Option<String>,
+}
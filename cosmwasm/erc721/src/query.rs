@@ -1,20 +1,134 @@
 use cosmwasm_schema::cw_serde;
+use cosmwasm_schema::serde::{de::DeserializeOwned, Serialize};
 
-use cosmwasm_std::{to_json_binary, Binary, Deps, Env, StdResult};
+use cosmwasm_std::{to_json_binary, Binary, Deps, Env, Order, StdError, StdResult};
+use cw_storage_plus::Bound;
 
 use crate::msg::{MinterResponse, QueryMsg};
-use crate::state::Erc721;
+use crate::state::{Approval, Erc721};
 
-impl<'a> Erc721<'a> {
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 100;
+
+impl<'a, T> Erc721<'a, T>
+where
+    T: Serialize + DeserializeOwned + Clone,
+{
     pub fn query(&self, deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         match msg {
+            QueryMsg::ContractInfo {} => to_json_binary(&self.contract_info(deps)?),
+            QueryMsg::NumTokens {} => to_json_binary(&self.num_tokens(deps)?),
+            QueryMsg::Tokens {
+                owner,
+                start_after,
+                limit,
+            } => to_json_binary(&self.tokens(deps, owner, start_after, limit)?),
+            QueryMsg::AllTokens { start_after, limit } => {
+                to_json_binary(&self.all_tokens(deps, start_after, limit)?)
+            }
             QueryMsg::Minter {} => to_json_binary(&self.minter(deps)?),
+            QueryMsg::Minters { start_after, limit } => {
+                to_json_binary(&self.minters(deps, start_after, limit)?)
+            }
+            QueryMsg::MaxSupply {} => to_json_binary(&self.max_supply(deps)?),
             QueryMsg::BalanceOf { owner } => to_json_binary(&self.balance_of(deps, env, owner)?),
             QueryMsg::OwnerOf { token_id } => to_json_binary(&self.owner_of(deps, env, token_id)?),
-            QueryMsg::Approval { token_id } => to_json_binary(&self.approval(deps, env, token_id)?),
+            QueryMsg::NftInfo { token_id } => to_json_binary(&self.nft_info(deps, token_id)?),
+            QueryMsg::AllNftInfo { token_id } => {
+                to_json_binary(&self.all_nft_info(deps, env, token_id)?)
+            }
+            QueryMsg::Approval {
+                token_id,
+                include_expired,
+            } => to_json_binary(&self.approval(
+                deps,
+                env,
+                token_id,
+                include_expired.unwrap_or(false),
+            )?),
+            QueryMsg::Operator {
+                owner,
+                operator,
+                include_expired,
+            } => to_json_binary(&self.operator(
+                deps,
+                env,
+                owner,
+                operator,
+                include_expired.unwrap_or(false),
+            )?),
+            QueryMsg::AllOperators {
+                owner,
+                include_expired,
+                start_after,
+                limit,
+            } => to_json_binary(&self.operators(
+                deps,
+                env,
+                owner,
+                include_expired.unwrap_or(false),
+                start_after,
+                limit,
+            )?),
         }
     }
 
+    fn contract_info(&self, deps: Deps) -> StdResult<ContractInfoResponse> {
+        let info = self.contract_info.load(deps.storage)?;
+
+        Ok(ContractInfoResponse {
+            name: info.name,
+            symbol: info.symbol,
+        })
+    }
+
+    fn num_tokens(&self, deps: Deps) -> StdResult<NumTokensResponse> {
+        let count = self.token_count(deps.storage)?;
+
+        Ok(NumTokensResponse { count })
+    }
+
+    fn tokens(
+        &self,
+        deps: Deps,
+        owner: String,
+        start_after: Option<u32>,
+        limit: Option<u32>,
+    ) -> StdResult<TokensResponse> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let owner_addr = deps.api.addr_validate(&owner)?;
+        let start = start_after.map(Bound::exclusive);
+
+        let tokens = self
+            .token_owner
+            .idx
+            .owner
+            .prefix(owner_addr)
+            .keys(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .collect::<StdResult<Vec<_>>>()?;
+
+        Ok(TokensResponse { tokens })
+    }
+
+    fn all_tokens(
+        &self,
+        deps: Deps,
+        start_after: Option<u32>,
+        limit: Option<u32>,
+    ) -> StdResult<TokensResponse> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start = start_after.map(Bound::exclusive);
+
+        let tokens = self
+            .token_owner
+            .keys(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .collect::<StdResult<Vec<_>>>()?;
+
+        Ok(TokensResponse { tokens })
+    }
+
     fn minter(&self, deps: Deps) -> StdResult<MinterResponse> {
         let minter = cw_ownable::get_ownership(deps.storage)?
             .owner
@@ -23,6 +137,38 @@ impl<'a> Erc721<'a> {
         Ok(MinterResponse { minter })
     }
 
+    fn minters(
+        &self,
+        deps: Deps,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<MintersResponse> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start_addr = start_after
+            .map(|addr| deps.api.addr_validate(&addr))
+            .transpose()?;
+        let start = start_addr.as_ref().map(Bound::exclusive);
+
+        let minters = self
+            .minters
+            .keys(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| item.map(|minter| minter.into_string()))
+            .collect::<StdResult<Vec<_>>>()?;
+
+        Ok(MintersResponse { minters })
+    }
+
+    fn max_supply(&self, deps: Deps) -> StdResult<MaxSupplyResponse> {
+        let max_supply = self.max_supply.may_load(deps.storage)?;
+        let minted = self
+            .minted_count
+            .may_load(deps.storage)?
+            .unwrap_or_default();
+
+        Ok(MaxSupplyResponse { max_supply, minted })
+    }
+
     fn balance_of(&self, deps: Deps, _env: Env, of: String) -> StdResult<BalanceOfResponse> {
         let of_addr = deps.api.addr_validate(&of)?;
         let balance = self.owned_tokens_count.may_load(deps.storage, &of_addr)?;
@@ -38,16 +184,127 @@ impl<'a> Erc721<'a> {
     fn owner_of(&self, deps: Deps, _env: Env, token_id: u32) -> StdResult<OwnerOfResponse> {
         let owner = self.token_owner.load(deps.storage, token_id)?;
 
-        Ok(OwnerOfResponse { owner })
+        Ok(OwnerOfResponse {
+            owner: owner.into_string(),
+        })
+    }
+
+    fn nft_info(&self, deps: Deps, token_id: u32) -> StdResult<NftInfoResponse<T>> {
+        let info = self.token_info.load(deps.storage, token_id)?;
+
+        Ok(NftInfoResponse {
+            token_uri: info.token_uri,
+            extension: info.extension,
+        })
+    }
+
+    fn all_nft_info(
+        &self,
+        deps: Deps,
+        env: Env,
+        token_id: u32,
+    ) -> StdResult<AllNftInfoResponse<T>> {
+        Ok(AllNftInfoResponse {
+            access: self.owner_of(deps, env.clone(), token_id)?,
+            approval: self.approval(deps, env, token_id, false)?,
+            info: self.nft_info(deps, token_id)?,
+        })
+    }
+
+    fn approval(
+        &self,
+        deps: Deps,
+        env: Env,
+        token_id: u32,
+        include_expired: bool,
+    ) -> StdResult<ApprovalResponse> {
+        let approval = self
+            .token_approvals
+            .may_load(deps.storage, token_id)?
+            .filter(|approval| include_expired || !approval.expires.is_expired(&env.block));
+
+        Ok(ApprovalResponse { approval })
+    }
+
+    fn operator(
+        &self,
+        deps: Deps,
+        env: Env,
+        owner: String,
+        operator: String,
+        include_expired: bool,
+    ) -> StdResult<OperatorResponse> {
+        let owner_addr = deps.api.addr_validate(&owner)?;
+        let operator_addr = deps.api.addr_validate(&operator)?;
+
+        let expires = self
+            .operators
+            .may_load(deps.storage, (&owner_addr, &operator_addr))?;
+        match expires {
+            Some(expires) if include_expired || !expires.is_expired(&env.block) => {
+                Ok(OperatorResponse {
+                    approval: Approval {
+                        spender: operator_addr,
+                        expires,
+                    },
+                })
+            }
+            _ => Err(StdError::not_found("Approval not found")),
+        }
     }
 
-    fn approval(&self, deps: Deps, _env: Env, token_id: u32) -> StdResult<ApprovalResponse> {
-        let approved = self.token_approvals.may_load(deps.storage, token_id)?;
+    fn operators(
+        &self,
+        deps: Deps,
+        env: Env,
+        owner: String,
+        include_expired: bool,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<OperatorsResponse> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let owner_addr = deps.api.addr_validate(&owner)?;
+        let start_addr = start_after
+            .map(|addr| deps.api.addr_validate(&addr))
+            .transpose()?;
+        let start = start_addr.as_ref().map(Bound::exclusive);
+
+        let operators = self
+            .operators
+            .prefix(&owner_addr)
+            .range(deps.storage, start, None, Order::Ascending)
+            .filter(|item| match item {
+                Ok((_, expires)) => include_expired || !expires.is_expired(&env.block),
+                Err(_) => true,
+            })
+            .take(limit)
+            .map(|item| item.map(|(spender, expires)| Approval { spender, expires }))
+            .collect::<StdResult<Vec<_>>>()?;
 
-        Ok(ApprovalResponse { approver: approved })
+        Ok(OperatorsResponse { operators })
     }
 }
 
+#[cw_serde]
+pub struct ContractInfoResponse {
+    /// Name of the NFT collection
+    pub name: String,
+    /// Symbol of the NFT collection
+    pub symbol: String,
+}
+
+#[cw_serde]
+pub struct NumTokensResponse {
+    /// Number of tokens in existence
+    pub count: u64,
+}
+
+#[cw_serde]
+pub struct TokensResponse {
+    /// Token IDs in ascending order
+    pub tokens: Vec<u32>,
+}
+
 #[cw_serde]
 pub struct BalanceOfResponse {
     /// Owner of the tokens
@@ -62,7 +319,49 @@ pub struct OwnerOfResponse {
     pub owner: String,
 }
 
+#[cw_serde]
+pub struct NftInfoResponse<T> {
+    /// Universal resource identifier for this NFT
+    pub token_uri: Option<String>,
+    /// On-chain metadata defined by the contract using this crate
+    pub extension: T,
+}
+
+#[cw_serde]
+pub struct AllNftInfoResponse<T> {
+    /// Owner of the token
+    pub access: OwnerOfResponse,
+    /// Spender approved for the token
+    pub approval: ApprovalResponse,
+    /// Metadata of the token
+    pub info: NftInfoResponse<T>,
+}
+
 #[cw_serde]
 pub struct ApprovalResponse {
-    pub approver: Option<String>,
+    pub approval: Option<Approval>,
+}
+
+#[cw_serde]
+pub struct OperatorResponse {
+    pub approval: Approval,
+}
+
+#[cw_serde]
+pub struct OperatorsResponse {
+    pub operators: Vec<Approval>,
+}
+
+#[cw_serde]
+pub struct MintersResponse {
+    /// Accounts allowed to mint besides the contract owner
+    pub minters: Vec<String>,
+}
+
+#[cw_serde]
+pub struct MaxSupplyResponse {
+    /// Maximum number of tokens that can be minted, unlimited if unset
+    pub max_supply: Option<u64>,
+    /// Number of tokens minted so far, including burned tokens
+    pub minted: u64,
 }
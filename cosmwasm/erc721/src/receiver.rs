@@ -0,0 +1,39 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{to_json_binary, Binary, CosmosMsg, StdResult, WasmMsg};
+
+/// Cw721ReceiveMsg should be de/serialized under `ReceiveNft()` variant in a ExecuteMsg
+#[cw_serde]
+pub struct Cw721ReceiveMsg {
+    /// Account that sent the token
+    pub sender: String,
+    /// ID of the received token
+    pub token_id: u32,
+    /// Message passed along with `SendNft`
+    pub msg: Binary,
+}
+
+impl Cw721ReceiveMsg {
+    /// serializes the message
+    pub fn into_binary(self) -> StdResult<Binary> {
+        let msg = ReceiverExecuteMsg::ReceiveNft(self);
+        to_json_binary(&msg)
+    }
+
+    /// creates a cosmos_msg sending this struct to the named contract
+    pub fn into_cosmos_msg<T: Into<String>>(self, contract_addr: T) -> StdResult<CosmosMsg> {
+        let msg = self.into_binary()?;
+        let execute = WasmMsg::Execute {
+            contract_addr: contract_addr.into(),
+            msg,
+            funds: vec![],
+        };
+        Ok(execute.into())
+    }
+}
+
+/// This is just a helper to properly serialize the above message.
+/// The actual receiver should include this variant in the larger ExecuteMsg enum
+#[cw_serde]
+enum ReceiverExecuteMsg {
+    ReceiveNft(Cw721ReceiveMsg),
+}
@@ -1,34 +1,149 @@
-use cosmwasm_std::Addr;
-use cw_storage_plus::Map;
+use cosmwasm_schema::cw_serde;
+use cosmwasm_schema::serde::{de::DeserializeOwned, Serialize};
+use cosmwasm_std::{Addr, Empty, StdResult, Storage};
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex};
+use cw_utils::Expiration;
 
 /// A token ID.
 pub type TokenId = u32;
 
-pub struct Erc721<'a> {
-    /// Mapping from token to owner.
-    pub token_owner: Map<'a, TokenId, String>,
+#[cw_serde]
+pub struct ContractInfo {
+    /// Name of the NFT collection
+    pub name: String,
+    /// Symbol of the NFT collection
+    pub symbol: String,
+}
+
+#[cw_serde]
+pub struct TokenInfo<T> {
+    /// Universal resource identifier for this NFT
+    pub token_uri: Option<String>,
+    /// On-chain metadata defined by the contract using this crate
+    pub extension: T,
+}
+
+#[cw_serde]
+pub struct Approval {
+    /// Account that can transfer/send the token
+    pub spender: Addr,
+    /// When the Approval expires (maybe Expiration::never)
+    pub expires: Expiration,
+}
+
+pub struct Erc721<'a, T> {
+    /// Name and symbol of the collection.
+    pub contract_info: Item<'a, ContractInfo>,
+    /// Mapping from token to owner, indexed by owner.
+    pub token_owner: IndexedMap<'a, TokenId, Addr, TokenIndexes<'a>>,
+    /// Mapping from token to its metadata.
+    pub token_info: Map<'a, TokenId, TokenInfo<T>>,
     /// Mapping from token to approvals users.
-    pub token_approvals: Map<'a, TokenId, String>,
+    pub token_approvals: Map<'a, TokenId, Approval>,
     /// Mapping from owner to number of owned token.
     pub owned_tokens_count: Map<'a, &'a Addr, u32>,
+    /// Mapping from (owner, operator) to the expiration of the operator approval.
+    pub operators: Map<'a, (&'a Addr, &'a Addr), Expiration>,
+    /// Number of tokens in existence.
+    pub token_count: Item<'a, u64>,
+    /// Accounts allowed to mint besides the contract owner.
+    pub minters: Map<'a, &'a Addr, Empty>,
+    /// Maximum number of tokens that can ever be minted, unlimited if unset.
+    pub max_supply: Item<'a, u64>,
+    /// Number of tokens minted so far, including burned tokens.
+    pub minted_count: Item<'a, u64>,
+    /// Lowest token ID that `MintNext` may assign.
+    pub next_token_id: Item<'a, TokenId>,
+}
+
+pub struct TokenIndexes<'a> {
+    /// Secondary index from owner to their tokens.
+    pub owner: MultiIndex<'a, Addr, Addr, TokenId>,
 }
 
-impl Default for Erc721<'static> {
+impl<'a> IndexList<Addr> for TokenIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Addr>> + '_> {
+        let v: Vec<&dyn Index<Addr>> = vec![&self.owner];
+        Box::new(v.into_iter())
+    }
+}
+
+impl<T> Default for Erc721<'static, T>
+where
+    T: Serialize + DeserializeOwned + Clone,
+{
     fn default() -> Self {
-        Self::new("token_owner", "token_approvals", "owned_tokens_count_key")
+        Self::new(
+            "contract_info",
+            "token_owner",
+            "token_owner__owner",
+            "token_info",
+            "token_approvals",
+            "owned_tokens_count_key",
+            "operators",
+            "token_count",
+            "minters",
+            "max_supply",
+            "minted_count",
+            "next_token_id",
+        )
     }
 }
 
-impl<'a> Erc721<'a> {
+impl<'a, T> Erc721<'a, T>
+where
+    T: Serialize + DeserializeOwned + Clone,
+{
+    #[allow(clippy::too_many_arguments)]
     fn new(
+        contract_info_key: &'a str,
         token_owner_key: &'a str,
+        token_owner_index_key: &'a str,
+        token_info_key: &'a str,
         token_approvals_key: &'a str,
         owned_tokens_count_key: &'a str,
+        operators_key: &'a str,
+        token_count_key: &'a str,
+        minters_key: &'a str,
+        max_supply_key: &'a str,
+        minted_count_key: &'a str,
+        next_token_id_key: &'a str,
     ) -> Self {
+        let indexes = TokenIndexes {
+            owner: MultiIndex::new(
+                |_pk, owner: &Addr| owner.clone(),
+                token_owner_key,
+                token_owner_index_key,
+            ),
+        };
         Self {
-            token_owner: Map::new(token_owner_key),
+            contract_info: Item::new(contract_info_key),
+            token_owner: IndexedMap::new(token_owner_key, indexes),
+            token_info: Map::new(token_info_key),
             token_approvals: Map::new(token_approvals_key),
             owned_tokens_count: Map::new(owned_tokens_count_key),
+            operators: Map::new(operators_key),
+            token_count: Item::new(token_count_key),
+            minters: Map::new(minters_key),
+            max_supply: Item::new(max_supply_key),
+            minted_count: Item::new(minted_count_key),
+            next_token_id: Item::new(next_token_id_key),
         }
     }
+
+    pub fn token_count(&self, storage: &dyn Storage) -> StdResult<u64> {
+        Ok(self.token_count.may_load(storage)?.unwrap_or_default())
+    }
+
+    pub fn increment_tokens(&self, storage: &mut dyn Storage) -> StdResult<u64> {
+        let val = self.token_count(storage)? + 1;
+        self.token_count.save(storage, &val)?;
+        Ok(val)
+    }
+
+    pub fn decrement_tokens(&self, storage: &mut dyn Storage) -> StdResult<u64> {
+        let val = self.token_count(storage)? - 1;
+        self.token_count.save(storage, &val)?;
+        Ok(val)
+    }
 }
@@ -0,0 +1,284 @@
+use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier};
+use cosmwasm_std::{
+    coin, coins, Addr, BankMsg, Env, Event, MemoryStorage, Order, OwnedDeps, Reply, ReplyOn,
+    Response, SubMsg, SubMsgResponse, SubMsgResult,
+};
+
+use crate::error::ContractError;
+use crate::msg::{ExecuteMsg, InstantiateMsg};
+use crate::reply::{END_REPLY_ID, START_REPLY_ID};
+use crate::state::{NftAuction, DEFAULT_DURATION};
+
+const SELLER: &str = "seller";
+const BIDDER1: &str = "bidder1";
+const BIDDER2: &str = "bidder2";
+const DENOM: &str = "uatom";
+
+type MockDeps = OwnedDeps<MemoryStorage, MockApi, MockQuerier>;
+
+/// Instantiates an auction with a starting bid of 100.
+fn instantiate_auction(push_refunds: Option<bool>) -> MockDeps {
+    let mut deps = mock_dependencies();
+    let contract = NftAuction::default();
+
+    let msg = InstantiateMsg {
+        nft_contract: Addr::unchecked("nft"),
+        nft_id: 1,
+        starting_bid: coin(100, DENOM),
+        push_refunds,
+        duration: None,
+    };
+    contract
+        .instantiate(deps.as_mut(), mock_env(), mock_info(SELLER, &[]), msg)
+        .unwrap();
+
+    deps
+}
+
+/// Instantiates and starts an auction with a starting bid of 100, as if the NFT transfer to
+/// the auction succeeded.
+fn setup_auction(push_refunds: Option<bool>) -> MockDeps {
+    let mut deps = instantiate_auction(push_refunds);
+    start(&mut deps).unwrap();
+    reply(&mut deps, START_REPLY_ID, transfer_succeeded()).unwrap();
+
+    deps
+}
+
+fn start(deps: &mut MockDeps) -> Result<Response, ContractError> {
+    NftAuction::default().execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(SELLER, &[]),
+        ExecuteMsg::Start {},
+    )
+}
+
+/// Ends the auction once bidding is over.
+fn end(deps: &mut MockDeps) -> Result<Response, ContractError> {
+    NftAuction::default().execute(
+        deps.as_mut(),
+        after_bidding(),
+        mock_info(BIDDER1, &[]),
+        ExecuteMsg::End {},
+    )
+}
+
+fn after_bidding() -> Env {
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(DEFAULT_DURATION);
+    env
+}
+
+fn reply(deps: &mut MockDeps, id: u64, result: SubMsgResult) -> Result<Response, ContractError> {
+    NftAuction::default().reply(deps.as_mut(), mock_env(), Reply { id, result })
+}
+
+fn transfer_succeeded() -> SubMsgResult {
+    SubMsgResult::Ok(SubMsgResponse {
+        events: vec![],
+        data: None,
+    })
+}
+
+fn transfer_failed() -> SubMsgResult {
+    SubMsgResult::Err("transfer failed".to_string())
+}
+
+fn bid(deps: &mut MockDeps, bidder: &str, amount: u128) -> Result<Response, ContractError> {
+    NftAuction::default().execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(bidder, &coins(amount, DENOM)),
+        ExecuteMsg::Bid {},
+    )
+}
+
+fn withdraw(deps: &mut MockDeps, bidder: &str) -> Result<Response, ContractError> {
+    NftAuction::default().execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(bidder, &[]),
+        ExecuteMsg::Withdraw {},
+    )
+}
+
+#[test]
+fn outbid_bid_can_only_be_withdrawn_once() {
+    let mut deps = setup_auction(None);
+    let contract = NftAuction::default();
+
+    let resp = bid(&mut deps, BIDDER1, 150).unwrap();
+    assert!(resp.messages.is_empty());
+    let resp = bid(&mut deps, BIDDER2, 200).unwrap();
+    assert!(resp.messages.is_empty());
+
+    let bidder1 = Addr::unchecked(BIDDER1);
+    assert_eq!(
+        contract.bids.may_load(&deps.storage, &bidder1).unwrap(),
+        Some(coin(150, DENOM))
+    );
+
+    let resp = withdraw(&mut deps, BIDDER1).unwrap();
+    assert_eq!(
+        resp.messages,
+        vec![SubMsg::new(BankMsg::Send {
+            to_address: BIDDER1.to_string(),
+            amount: coins(150, DENOM),
+        })]
+    );
+    assert_eq!(
+        contract.bids.may_load(&deps.storage, &bidder1).unwrap(),
+        None
+    );
+
+    let err = withdraw(&mut deps, BIDDER1).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::NoWithdrawableBid {
+            bidder: BIDDER1.to_string(),
+        }
+    );
+}
+
+#[test]
+fn push_refunds_return_outbid_bid_right_away() {
+    let mut deps = setup_auction(Some(true));
+    let contract = NftAuction::default();
+
+    // The starting bid is not refunded to anyone
+    let resp = bid(&mut deps, BIDDER1, 150).unwrap();
+    assert!(resp.messages.is_empty());
+
+    let resp = bid(&mut deps, BIDDER2, 200).unwrap();
+    assert_eq!(
+        resp.messages,
+        vec![SubMsg::new(BankMsg::Send {
+            to_address: BIDDER1.to_string(),
+            amount: coins(150, DENOM),
+        })]
+    );
+
+    // Nothing is left to withdraw
+    assert!(contract
+        .bids
+        .range(&deps.storage, None, None, Order::Ascending)
+        .next()
+        .is_none());
+
+    let err = withdraw(&mut deps, BIDDER1).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::NoWithdrawableBid {
+            bidder: BIDDER1.to_string(),
+        }
+    );
+}
+
+#[test]
+fn start_event_is_emitted_once_the_nft_is_transferred() {
+    let mut deps = instantiate_auction(None);
+
+    let resp = start(&mut deps).unwrap();
+    assert_eq!(resp.messages.len(), 1);
+    assert_eq!(resp.messages[0].id, START_REPLY_ID);
+    assert_eq!(resp.messages[0].reply_on, ReplyOn::Always);
+    assert!(resp.events.is_empty());
+
+    let resp = reply(&mut deps, START_REPLY_ID, transfer_succeeded()).unwrap();
+    let end_at = mock_env().block.time.plus_seconds(DEFAULT_DURATION);
+    assert_eq!(
+        resp.events,
+        vec![Event::new("start")
+            .add_attribute("action", "start")
+            .add_attribute("caller", SELLER)
+            .add_attribute("end_at", end_at.to_string())]
+    );
+}
+
+#[test]
+fn failed_start_puts_the_auction_back_to_not_started() {
+    let mut deps = instantiate_auction(None);
+    let contract = NftAuction::default();
+    start(&mut deps).unwrap();
+
+    let resp = reply(&mut deps, START_REPLY_ID, transfer_failed()).unwrap();
+    assert_eq!(
+        resp.events,
+        vec![Event::new("start_failed").add_attribute("error", "transfer failed")]
+    );
+
+    let status = contract.status.load(&deps.storage).unwrap();
+    assert!(!status.started);
+    assert_eq!(status.end_at, None);
+    assert_eq!(status.last_error, Some("transfer failed".to_string()));
+    let err = bid(&mut deps, BIDDER1, 150).unwrap_err();
+    assert_eq!(err, ContractError::NotStarted);
+
+    // The seller can start again once the NFT is approved
+    start(&mut deps).unwrap();
+    let status = contract.status.load(&deps.storage).unwrap();
+    assert!(status.started);
+    assert_eq!(status.last_error, None);
+}
+
+#[test]
+fn failed_settlement_can_be_retried() {
+    let mut deps = setup_auction(None);
+    let contract = NftAuction::default();
+    bid(&mut deps, BIDDER1, 150).unwrap();
+
+    let resp = end(&mut deps).unwrap();
+    assert_eq!(resp.messages[0].id, END_REPLY_ID);
+    assert_eq!(resp.messages[0].reply_on, ReplyOn::Error);
+
+    let resp = reply(&mut deps, END_REPLY_ID, transfer_failed()).unwrap();
+    assert_eq!(
+        resp.events,
+        vec![Event::new("settlement_failed").add_attribute("error", "transfer failed")]
+    );
+    let status = contract.status.load(&deps.storage).unwrap();
+    assert!(status.ended);
+    assert!(status.settlement_failed);
+    assert_eq!(status.last_error, Some("transfer failed".to_string()));
+
+    let resp = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(BIDDER2, &[]),
+            ExecuteMsg::RetrySettlement {},
+        )
+        .unwrap();
+    assert_eq!(resp.messages.len(), 1);
+    assert_eq!(resp.messages[0].id, END_REPLY_ID);
+    assert_eq!(resp.messages[0].reply_on, ReplyOn::Error);
+
+    let status = contract.status.load(&deps.storage).unwrap();
+    assert!(!status.settlement_failed);
+    assert_eq!(status.last_error, None);
+}
+
+#[test]
+fn retry_settlement_requires_a_failed_settlement() {
+    let mut deps = setup_auction(None);
+    end(&mut deps).unwrap();
+
+    let err = NftAuction::default()
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(SELLER, &[]),
+            ExecuteMsg::RetrySettlement {},
+        )
+        .unwrap_err();
+    assert_eq!(err, ContractError::SettlementNotFailed);
+}
+
+#[test]
+fn unknown_reply_id_is_rejected() {
+    let mut deps = setup_auction(None);
+
+    let err = reply(&mut deps, 99, transfer_failed()).unwrap_err();
+    assert_eq!(err, ContractError::UnknownReplyId { id: 99 });
+}
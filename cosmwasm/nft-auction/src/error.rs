@@ -28,6 +28,33 @@ pub enum ContractError {
     #[error("auction bid too low")]
     BiddingTooLow,
 
+    #[error("auction duration shorter than {min} seconds")]
+    DurationTooShort { min: u64 },
+
+    #[error("auction duration longer than {max} seconds")]
+    DurationTooLong { max: u64 },
+
     #[error("No withdrawable bid for {bidder}")]
     NoWithdrawableBid { bidder: String },
+
+    #[error("auction settlement did not fail")]
+    SettlementNotFailed,
+
+    #[error("Unknown reply id: {id}")]
+    UnknownReplyId { id: u64 },
+
+    #[error("Cannot migrate from a different contract: {previous_contract}")]
+    CannotMigrate { previous_contract: String },
+
+    #[error("Cannot migrate from newer version ({from}) to older ({to})")]
+    CannotDowngrade { from: String, to: String },
+
+    #[error("Semver parsing error: {0}")]
+    SemVer(String),
+}
+
+impl From<semver::Error> for ContractError {
+    fn from(err: semver::Error) -> Self {
+        Self::SemVer(err.to_string())
+    }
 }
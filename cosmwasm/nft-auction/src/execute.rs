@@ -1,10 +1,13 @@
-use crate::state::{Config, NftAuction, Status};
+use crate::state::{Config, NftAuction, Status, DEFAULT_DURATION, MAX_DURATION, MIN_DURATION};
 use cosmwasm_std::{
-    to_json_binary, BankMsg, Coin, CosmosMsg, DepsMut, Env, Event, MessageInfo, Response, WasmMsg,
+    to_json_binary, Addr, BankMsg, Coin, DepsMut, Env, MessageInfo, Response, StdResult, SubMsg,
+    WasmMsg,
 };
 
 use crate::error::ContractError;
 use crate::msg::{ExecuteMsg, InstantiateMsg};
+use crate::reply::{END_REPLY_ID, START_REPLY_ID};
+use crate::{CONTRACT_NAME, CONTRACT_VERSION};
 
 impl<'a> NftAuction<'a> {
     pub fn instantiate(
@@ -16,11 +19,22 @@ impl<'a> NftAuction<'a> {
     ) -> Result<Response, ContractError> {
         let caller = &info.sender;
         cw_ownable::initialize_owner(deps.storage, deps.api, Some(caller.as_ref()))?;
+        cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+        let duration = msg.duration.unwrap_or(DEFAULT_DURATION);
+        if duration < MIN_DURATION {
+            return Err(ContractError::DurationTooShort { min: MIN_DURATION });
+        }
+        if duration > MAX_DURATION {
+            return Err(ContractError::DurationTooLong { max: MAX_DURATION });
+        }
 
         let config = Config {
             nft_contract: msg.nft_contract,
             nft_id: msg.nft_id,
             starting_bid: msg.starting_bid.to_owned(),
+            push_refunds: msg.push_refunds.unwrap_or(false),
+            duration,
         };
 
         self.config.save(deps.storage, &config)?;
@@ -31,6 +45,8 @@ impl<'a> NftAuction<'a> {
             end_at: None,
             highest_bidder: None,
             highest_bid: msg.starting_bid.to_owned(),
+            settlement_failed: false,
+            last_error: None,
         };
         self.status.save(deps.storage, &status)?;
 
@@ -49,6 +65,7 @@ impl<'a> NftAuction<'a> {
             ExecuteMsg::Bid {} => self.bid(deps, env, info),
             ExecuteMsg::Withdraw {} => self.withdraw(deps, info),
             ExecuteMsg::End {} => self.end(deps, env, info),
+            ExecuteMsg::RetrySettlement {} => self.retry_settlement(deps, info),
         }
     }
 
@@ -63,35 +80,20 @@ impl<'a> NftAuction<'a> {
             return Err(ContractError::AlreadyStarted);
         }
 
+        let config = self.config.load(deps.storage)?;
+
         status.started = true;
-        status.end_at = Some(env.block.time.plus_seconds(5 * 60));
+        status.end_at = Some(env.block.time.plus_seconds(config.duration));
+        status.last_error = None;
         self.status.save(deps.storage, &status)?;
 
-        let Config {
-            nft_contract,
-            nft_id,
-            ..
-        } = self.config.load(deps.storage)?;
-
-        // Response message: transfer NFT from sender to this contract + nft ID
-        let erc_transfer_msg = erc721::ExecuteMsg::TransferNft {
-            recipient: env.contract.address.to_string(),
-            token_id: nft_id,
-        };
-        let erc_transfer_msg = WasmMsg::Execute {
-            contract_addr: nft_contract.into_string(),
-            msg: to_json_binary(&erc_transfer_msg)?,
-            funds: vec![],
-        };
-
-        let event = Event::new("start")
-            .add_attribute("action", "start")
-            .add_attribute("caller", info.sender.as_str())
-            .add_attribute("end_at", status.end_at.unwrap().to_string());
+        // Response message: transfer NFT from sender to this contract + nft ID. The reply
+        // emits the start event once the transfer succeeded, or puts the auction back to not
+        // started if it failed
+        let erc_transfer_msg = transfer_nft_msg(&config, &env.contract.address)?;
 
-        let resp = Response::new()
-            .add_message(erc_transfer_msg)
-            .add_event(event);
+        let resp =
+            Response::new().add_submessage(SubMsg::reply_always(erc_transfer_msg, START_REPLY_ID));
 
         Ok(resp)
     }
@@ -118,21 +120,33 @@ impl<'a> NftAuction<'a> {
             return Err(ContractError::BiddingTooLow);
         }
 
-        // If there is already a highest bidder, it will be added to the list of bids
+        let mut resp = Response::new();
+
+        // If there is already a highest bidder, its bid is either refunded right away or
+        // added to the list of bids to withdraw
+        let push_refunds = self.config.load(deps.storage)?.push_refunds;
         if let Some(prev_highest_addr) = status.highest_bidder {
-            let prev_highest = self.bids.may_load(deps.storage, &prev_highest_addr)?;
-            match prev_highest {
-                Some(prev_coin) => {
-                    let total_bid = Coin::new(
-                        (prev_coin.amount + status.highest_bid.amount).u128(),
-                        prev_coin.denom,
-                    );
-                    self.bids
-                        .save(deps.storage, &prev_highest_addr, &total_bid)?;
+            if push_refunds {
+                resp = resp.add_message(BankMsg::Send {
+                    to_address: prev_highest_addr.to_string(),
+                    amount: vec![status.highest_bid.to_owned()],
+                });
+            } else {
+                let prev_highest = self.bids.may_load(deps.storage, &prev_highest_addr)?;
+                match prev_highest {
+                    Some(prev_coin) => {
+                        let total_bid = Coin::new(
+                            (prev_coin.amount + status.highest_bid.amount).u128(),
+                            prev_coin.denom,
+                        );
+                        self.bids
+                            .save(deps.storage, &prev_highest_addr, &total_bid)?;
+                    }
+                    None => {
+                        self.bids
+                            .save(deps.storage, &prev_highest_addr, &status.highest_bid)?
+                    }
                 }
-                None => self
-                    .bids
-                    .save(deps.storage, &prev_highest_addr, &status.highest_bid)?,
             }
         }
 
@@ -140,7 +154,7 @@ impl<'a> NftAuction<'a> {
         status.highest_bid = coin.unwrap().to_owned();
         self.status.save(deps.storage, &status)?;
 
-        let resp = Response::new()
+        let resp = resp
             .add_attribute("action", "bid")
             .add_attribute("bidder", info.sender.as_str())
             .add_attribute(
@@ -156,6 +170,9 @@ impl<'a> NftAuction<'a> {
         let bid = self.bids.may_load(deps.storage, &caller)?;
         match bid {
             Some(coin) => {
+                // Clear the record before paying out, so the bid can only be withdrawn once
+                self.bids.remove(deps.storage, &caller);
+
                 // Add bank transfer to message
                 let bank_msg = BankMsg::Send {
                     to_address: caller.to_string(),
@@ -198,58 +215,81 @@ impl<'a> NftAuction<'a> {
         let config = self.config.load(deps.storage)?;
         self.status.save(deps.storage, &status)?;
 
-        let mut msgs: Vec<CosmosMsg> = Vec::new();
         let seller = cw_ownable::get_ownership(deps.storage)
             .unwrap()
             .owner
             .unwrap();
-        match status.highest_bidder.to_owned() {
-            Some(bidder) => {
-                // Send NFT to bidder and bid to seller
-                let erc_transfer_msg = erc721::ExecuteMsg::TransferNft {
-                    recipient: bidder.to_string(),
-                    token_id: config.nft_id,
-                };
-                let erc_transfer_msg = WasmMsg::Execute {
-                    contract_addr: config.nft_contract.into_string(),
-                    msg: to_json_binary(&erc_transfer_msg)?,
-                    funds: vec![],
-                };
-                msgs.push(erc_transfer_msg.into());
 
-                // Add bank transfer to seller
-                let bank_msg = BankMsg::Send {
-                    to_address: seller.to_string(),
-                    amount: vec![status.highest_bid.to_owned()],
-                };
-                msgs.push(bank_msg.into());
-            }
-            None => {
-                // Send NFT back to seller
-                let erc_transfer_msg = erc721::ExecuteMsg::TransferNft {
-                    recipient: seller.to_string(),
-                    token_id: config.nft_id,
-                };
-                let erc_transfer_msg = WasmMsg::Execute {
-                    contract_addr: config.nft_contract.into_string(),
-                    msg: to_json_binary(&erc_transfer_msg)?,
-                    funds: vec![],
-                };
-                msgs.push(erc_transfer_msg.into());
-            }
+        // Send NFT to bidder, or back to seller if nobody bid. If the transfer fails, the
+        // reply marks the settlement as failed so it can be retried
+        let recipient = status.highest_bidder.as_ref().unwrap_or(&seller);
+        let erc_transfer_msg = transfer_nft_msg(&config, recipient)?;
+        let mut resp =
+            Response::new().add_submessage(SubMsg::reply_on_error(erc_transfer_msg, END_REPLY_ID));
+
+        // Add bank transfer of the bid to seller
+        if status.highest_bidder.is_some() {
+            resp = resp.add_message(BankMsg::Send {
+                to_address: seller.to_string(),
+                amount: vec![status.highest_bid.to_owned()],
+            });
         }
 
-        let resp = Response::new()
+        let resp = resp
             .add_attribute("action", "end")
-            .add_messages(msgs)
             .add_attribute("caller", caller.as_str())
             .add_attribute("winner", status.highest_bidder.unwrap_or(caller).as_str())
             .add_attribute(
                 "value",
                 status.highest_bid.amount.to_string() + " " + &status.highest_bid.denom,
             );
-        // Optimization: send money back to bidders automatically
 
         Ok(resp)
     }
+
+    fn retry_settlement(
+        &self,
+        deps: DepsMut,
+        info: MessageInfo,
+    ) -> Result<Response, ContractError> {
+        let mut status = self.status.load(deps.storage)?;
+        if !status.settlement_failed {
+            return Err(ContractError::SettlementNotFailed);
+        }
+
+        status.settlement_failed = false;
+        status.last_error = None;
+        self.status.save(deps.storage, &status)?;
+
+        let config = self.config.load(deps.storage)?;
+        let seller = cw_ownable::get_ownership(deps.storage)
+            .unwrap()
+            .owner
+            .unwrap();
+        let recipient = status.highest_bidder.as_ref().unwrap_or(&seller);
+        let erc_transfer_msg = transfer_nft_msg(&config, recipient)?;
+
+        let resp = Response::new()
+            .add_submessage(SubMsg::reply_on_error(erc_transfer_msg, END_REPLY_ID))
+            .add_attribute("action", "retry_settlement")
+            .add_attribute("caller", info.sender.as_str())
+            .add_attribute("recipient", recipient.as_str());
+
+        Ok(resp)
+    }
+}
+
+/// Transfers the auctioned NFT to `recipient`.
+fn transfer_nft_msg(config: &Config, recipient: &Addr) -> StdResult<WasmMsg> {
+    let erc_transfer_msg = erc721::ExecuteMsg::<erc721::Extension>::TransferNft {
+        recipient: recipient.to_string(),
+        token_id: config.nft_id,
+    };
+    let erc_transfer_msg = WasmMsg::Execute {
+        contract_addr: config.nft_contract.to_string(),
+        msg: to_json_binary(&erc_transfer_msg)?,
+        funds: vec![],
+    };
+
+    Ok(erc_transfer_msg)
 }
@@ -1,18 +1,29 @@
 pub mod error;
 mod execute;
+mod migrate;
 pub mod msg;
+mod query;
+mod reply;
 pub mod state;
 
+#[cfg(test)]
+mod contract_tests;
+
 pub use crate::error::ContractError;
-pub use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+pub use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
+pub use crate::query::{AllBidsResponse, BidResponse, ConfigResponse, StatusResponse};
 pub use crate::state::NftAuction;
 
+// Version info for migration
+pub const CONTRACT_NAME: &str = "crates.io:nft-auction";
+pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
 pub mod entry {
     use super::*;
 
     #[cfg(not(feature = "library"))]
     use cosmwasm_std::entry_point;
-    use cosmwasm_std::{DepsMut, Env, MessageInfo, Response};
+    use cosmwasm_std::{Binary, Deps, DepsMut, Env, MessageInfo, Reply, Response, StdResult};
 
     #[cfg_attr(not(feature = "library"), entry_point)]
     pub fn instantiate(
@@ -35,4 +46,22 @@ pub mod entry {
         let contract = NftAuction::default();
         contract.execute(deps, env, info, msg)
     }
+
+    #[cfg_attr(not(feature = "library"), entry_point)]
+    pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+        let contract = NftAuction::default();
+        contract.query(deps, env, msg)
+    }
+
+    #[cfg_attr(not(feature = "library"), entry_point)]
+    pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+        let contract = NftAuction::default();
+        contract.reply(deps, env, msg)
+    }
+
+    #[cfg_attr(not(feature = "library"), entry_point)]
+    pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+        let contract = NftAuction::default();
+        contract.migrate(deps, env, msg)
+    }
 }
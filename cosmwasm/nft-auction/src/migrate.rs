@@ -0,0 +1,48 @@
+use cosmwasm_std::{DepsMut, Env, Response};
+use semver::Version;
+
+use crate::error::ContractError;
+use crate::msg::MigrateMsg;
+use crate::state::NftAuction;
+use crate::{CONTRACT_NAME, CONTRACT_VERSION};
+
+impl<'a> NftAuction<'a> {
+    pub fn migrate(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        _msg: MigrateMsg,
+    ) -> Result<Response, ContractError> {
+        // Contracts instantiated before versioning was added have no stored version
+        let from_version = match cw2::get_contract_version(deps.storage) {
+            Ok(stored) => {
+                if stored.contract != CONTRACT_NAME {
+                    return Err(ContractError::CannotMigrate {
+                        previous_contract: stored.contract,
+                    });
+                }
+                stored.version.parse::<Version>()?
+            }
+            Err(_) => Version::new(0, 1, 0),
+        };
+        let to_version = CONTRACT_VERSION.parse::<Version>()?;
+        if from_version > to_version {
+            return Err(ContractError::CannotDowngrade {
+                from: from_version.to_string(),
+                to: to_version.to_string(),
+            });
+        }
+
+        // 0.2.0 added `push_refunds` and `duration` to `Config`, and `settlement_failed` and
+        // `last_error` to `Status`. Their serde defaults read the 0.1.0 layout as an auction
+        // with pull refunds, the 5 minute duration and no failed settlement, so there is no
+        // state to rewrite
+
+        cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "migrate")
+            .add_attribute("from_version", from_version.to_string())
+            .add_attribute("to_version", to_version.to_string()))
+    }
+}
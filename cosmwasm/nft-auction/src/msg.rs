@@ -1,6 +1,8 @@
-use cosmwasm_schema::cw_serde;
+use cosmwasm_schema::{cw_serde, QueryResponses};
 use cosmwasm_std::{Addr, Coin};
 
+use crate::query::{AllBidsResponse, BidResponse, ConfigResponse, StatusResponse};
+
 #[cw_serde]
 pub struct InstantiateMsg {
     /// Name of the NFT contract
@@ -9,6 +11,12 @@ pub struct InstantiateMsg {
     pub nft_id: u32,
 
     pub starting_bid: Coin,
+    /// Send the previous highest bid back as soon as it is outbid, instead of
+    /// letting the bidder withdraw it. Defaults to false
+    pub push_refunds: Option<bool>,
+    /// Bidding duration in seconds, counted from the start of the auction.
+    /// Defaults to 5 minutes
+    pub duration: Option<u64>,
 }
 
 #[cw_serde]
@@ -17,9 +25,29 @@ pub enum ExecuteMsg {
     Bid {},
     Withdraw {},
     End {},
+    /// Send the NFT again after its transfer failed when the auction ended
+    RetrySettlement {},
 }
 
 #[cw_serde]
+#[derive(QueryResponses)]
 pub enum QueryMsg {
-    Dummy {},
+    /// Return the auctioned NFT and the starting bid
+    #[returns(ConfigResponse)]
+    Config {},
+    /// Return the auction status, including the time remaining until the end
+    #[returns(StatusResponse)]
+    Status {},
+    /// Return the withdrawable bid of the given address
+    #[returns(BidResponse)]
+    Bid { address: String },
+    /// Return all withdrawable bids, paginated by bidder address
+    #[returns(AllBidsResponse)]
+    AllBids {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
 }
+
+#[cw_serde]
+pub struct MigrateMsg {}
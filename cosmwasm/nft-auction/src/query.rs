@@ -0,0 +1,148 @@
+use cosmwasm_schema::cw_serde;
+
+use cosmwasm_std::{to_json_binary, Addr, Binary, Coin, Deps, Env, Order, StdResult, Timestamp};
+use cw_storage_plus::Bound;
+
+use crate::msg::QueryMsg;
+use crate::state::{Config, NftAuction, Status};
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
+impl<'a> NftAuction<'a> {
+    pub fn query(&self, deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+        match msg {
+            QueryMsg::Config {} => to_json_binary(&self.query_config(deps)?),
+            QueryMsg::Status {} => to_json_binary(&self.query_status(deps, env)?),
+            QueryMsg::Bid { address } => to_json_binary(&self.query_bid(deps, address)?),
+            QueryMsg::AllBids { start_after, limit } => {
+                to_json_binary(&self.query_all_bids(deps, start_after, limit)?)
+            }
+        }
+    }
+
+    fn query_config(&self, deps: Deps) -> StdResult<ConfigResponse> {
+        let Config {
+            nft_contract,
+            nft_id,
+            starting_bid,
+            push_refunds,
+            duration,
+        } = self.config.load(deps.storage)?;
+        let seller = cw_ownable::get_ownership(deps.storage)?.owner;
+
+        Ok(ConfigResponse {
+            nft_contract,
+            nft_id,
+            starting_bid,
+            push_refunds,
+            duration,
+            seller,
+        })
+    }
+
+    fn query_status(&self, deps: Deps, env: Env) -> StdResult<StatusResponse> {
+        let Status {
+            started,
+            ended,
+            end_at,
+            highest_bidder,
+            highest_bid,
+            settlement_failed,
+            last_error,
+        } = self.status.load(deps.storage)?;
+
+        // Seconds left for bidding, zero once the end time has passed
+        let time_remaining =
+            end_at.map(|end_at| end_at.seconds().saturating_sub(env.block.time.seconds()));
+
+        Ok(StatusResponse {
+            started,
+            ended,
+            end_at,
+            time_remaining,
+            highest_bidder,
+            highest_bid,
+            settlement_failed,
+            last_error,
+        })
+    }
+
+    fn query_bid(&self, deps: Deps, address: String) -> StdResult<BidResponse> {
+        let addr = deps.api.addr_validate(&address)?;
+        let bid = self.bids.may_load(deps.storage, &addr)?;
+
+        Ok(BidResponse { address: addr, bid })
+    }
+
+    fn query_all_bids(
+        &self,
+        deps: Deps,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<AllBidsResponse> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start_addr = start_after
+            .map(|addr| deps.api.addr_validate(&addr))
+            .transpose()?;
+        let start = start_addr.as_ref().map(Bound::exclusive);
+
+        let bids = self
+            .bids
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| {
+                item.map(|(address, bid)| BidResponse {
+                    address,
+                    bid: Some(bid),
+                })
+            })
+            .collect::<StdResult<Vec<_>>>()?;
+
+        Ok(AllBidsResponse { bids })
+    }
+}
+
+#[cw_serde]
+pub struct ConfigResponse {
+    /// Address of the NFT contract
+    pub nft_contract: Addr,
+    /// ID of the auctioned NFT
+    pub nft_id: u32,
+    /// Minimum bid of the auction
+    pub starting_bid: Coin,
+    /// Whether outbid bids are refunded right away
+    pub push_refunds: bool,
+    /// Bidding duration in seconds
+    pub duration: u64,
+    /// Seller receiving the highest bid
+    pub seller: Option<Addr>,
+}
+
+#[cw_serde]
+pub struct StatusResponse {
+    pub started: bool,
+    pub ended: bool,
+    pub end_at: Option<Timestamp>,
+    /// Seconds until bidding ends, `None` if the auction has not started
+    pub time_remaining: Option<u64>,
+    pub highest_bidder: Option<Addr>,
+    pub highest_bid: Coin,
+    /// Whether the NFT still has to be delivered with `RetrySettlement`
+    pub settlement_failed: bool,
+    /// Error of the last failed NFT transfer
+    pub last_error: Option<String>,
+}
+
+#[cw_serde]
+pub struct BidResponse {
+    /// Address of the bidder
+    pub address: Addr,
+    /// Amount the bidder can withdraw
+    pub bid: Option<Coin>,
+}
+
+#[cw_serde]
+pub struct AllBidsResponse {
+    pub bids: Vec<BidResponse>,
+}
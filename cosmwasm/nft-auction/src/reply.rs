@@ -0,0 +1,46 @@
+use cosmwasm_std::{DepsMut, Env, Event, Reply, Response, SubMsgResult};
+
+use crate::error::ContractError;
+use crate::state::NftAuction;
+
+/// Reply to the NFT transfer taking custody of the NFT in `Start`.
+pub const START_REPLY_ID: u64 = 1;
+/// Reply to the NFT transfer settling the auction in `End` and `RetrySettlement`.
+pub const END_REPLY_ID: u64 = 2;
+
+impl<'a> NftAuction<'a> {
+    pub fn reply(&self, deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+        let mut status = self.status.load(deps.storage)?;
+        let (event, error) = match (msg.id, msg.result) {
+            (START_REPLY_ID, SubMsgResult::Ok(_)) => {
+                // The auction holds the NFT, so bidding is open
+                let seller = cw_ownable::get_ownership(deps.storage)?.owner.unwrap();
+                let event = Event::new("start")
+                    .add_attribute("action", "start")
+                    .add_attribute("caller", seller.as_str())
+                    .add_attribute("end_at", status.end_at.unwrap().to_string());
+                return Ok(Response::new().add_event(event));
+            }
+            (START_REPLY_ID, SubMsgResult::Err(error)) => {
+                // The NFT never reached the auction, so it is back to not started
+                status.started = false;
+                status.end_at = None;
+                (Event::new("start_failed"), error)
+            }
+            // The settlement is sent with `reply_on_error`, so only failures reach here
+            (END_REPLY_ID, SubMsgResult::Ok(_)) => return Ok(Response::default()),
+            (END_REPLY_ID, SubMsgResult::Err(error)) => {
+                // The auction stays ended, holding the NFT until the settlement is retried
+                status.settlement_failed = true;
+                (Event::new("settlement_failed"), error)
+            }
+            (id, _) => return Err(ContractError::UnknownReplyId { id }),
+        };
+        status.last_error = Some(error.to_owned());
+        self.status.save(deps.storage, &status)?;
+
+        let resp = Response::new().add_event(event.add_attribute("error", error));
+
+        Ok(resp)
+    }
+}
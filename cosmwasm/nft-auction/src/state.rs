@@ -2,6 +2,13 @@ use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{Addr, Coin, Timestamp};
 use cw_storage_plus::{Item, Map};
 
+/// Bidding duration, in seconds, of auctions instantiated without one
+pub const DEFAULT_DURATION: u64 = 5 * 60;
+/// Shortest bidding duration accepted, in seconds
+pub const MIN_DURATION: u64 = 60;
+/// Longest bidding duration accepted, in seconds
+pub const MAX_DURATION: u64 = 30 * 24 * 60 * 60;
+
 #[cw_serde]
 pub struct Config {
     pub nft_contract: Addr,
@@ -9,6 +16,18 @@ pub struct Config {
     pub nft_id: u32,
 
     pub starting_bid: Coin,
+
+    /// Refund outbid bidders right away instead of letting them withdraw
+    #[serde(default)]
+    pub push_refunds: bool,
+
+    /// Bidding duration in seconds, counted from the start of the auction
+    #[serde(default = "default_duration")]
+    pub duration: u64,
+}
+
+fn default_duration() -> u64 {
+    DEFAULT_DURATION
 }
 
 #[cw_serde]
@@ -18,6 +37,12 @@ pub struct Status {
     pub end_at: Option<Timestamp>,
     pub highest_bidder: Option<Addr>,
     pub highest_bid: Coin,
+    /// The NFT could not be delivered when the auction ended, see `RetrySettlement`
+    #[serde(default)]
+    pub settlement_failed: bool,
+    /// Error of the last failed NFT transfer
+    #[serde(default)]
+    pub last_error: Option<String>,
 }
 
 pub struct NftAuction<'a> {
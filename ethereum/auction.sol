@@ -16,7 +16,11 @@ contract EnglishAuction {
     IERC721 public nft;
     uint256 public nftId;
 
+    uint256 public constant MIN_DURATION = 1 minutes;
+    uint256 public constant MAX_DURATION = 30 days;
+
     address payable public seller;
+    uint256 public duration;
     uint256 public endAt;
     bool public started;
     bool public ended;
@@ -25,9 +29,13 @@ contract EnglishAuction {
     uint256 public highestBid;
     mapping(address => uint256) public bids;
 
-    constructor(address _nft, uint256 _nftId, uint256 _startingBid) {
+    constructor(address _nft, uint256 _nftId, uint256 _startingBid, uint256 _duration) {
+        require(_duration >= MIN_DURATION, "duration too short");
+        require(_duration <= MAX_DURATION, "duration too long");
+
         nft = IERC721(_nft);
         nftId = _nftId;
+        duration = _duration;
 
         seller = payable(msg.sender);
         highestBid = _startingBid;
@@ -39,7 +47,7 @@ contract EnglishAuction {
 
         nft.transferFrom(msg.sender, address(this), nftId);
         started = true;
-        endAt = block.timestamp + 7 days;
+        endAt = block.timestamp + duration;
 
         emit Start();
     }
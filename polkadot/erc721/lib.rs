@@ -2,20 +2,65 @@
 
 #[ink::contract]
 pub mod erc721 {
-    use ink::storage::Mapping;
+    use ink::env::{
+        call::{build_call, ExecutionInput, Selector},
+        DefaultEnvironment,
+    };
+    use ink::prelude::{string::String, vec::Vec};
+    use ink::storage::{traits::ManualKey, Lazy, Mapping};
 
     /// A token ID.
     pub type TokenId = u32;
 
+    /// Selector of the message a receiving contract must implement to accept tokens through
+    /// `safe_transfer_from`. The receiver acknowledges the transfer by returning these bytes.
+    pub const ON_ERC721_RECEIVED: [u8; 4] = ink::selector_bytes!("on_erc721_received");
+
+    /// Version of the storage layout written by this code. Bump it whenever the layout
+    /// changes, and migrate older layouts in `migrate`.
+    ///
+    /// Version 1 is the first upgradable layout. Collections deployed before it have no
+    /// `set_code` and cannot be upgraded; their storage does not decode with this code.
+    pub const STORAGE_VERSION: u32 = 1;
+
+    /// Storage key of the layout version, fixed so that every code version finds it.
+    const STORAGE_VERSION_KEY: u32 = 0x7665_7273;
+    /// Storage key of the upgrade admin.
+    const ADMIN_KEY: u32 = 0x6164_6d6e;
+
     #[ink(storage)]
     #[derive(Default)]
     pub struct Erc721 {
+        /// Owner of the contract, the only account allowed to mint.
+        minter: Option<AccountId>,
+        /// Account the ownership is being transferred to, until it accepts.
+        pending_minter: Option<AccountId>,
+        /// Name of the NFT collection.
+        name: String,
+        /// Symbol of the NFT collection.
+        symbol: String,
         /// Mapping from token to owner.
         token_owner: Mapping<TokenId, AccountId>,
         /// Mapping from token to approvals users.
         token_approvals: Mapping<TokenId, AccountId>,
         /// Mapping from owner to number of owned token.
         owned_tokens_count: Mapping<AccountId, u32>,
+        /// Mapping from (owner, index) to the token at that index of the owner's token list.
+        owned_tokens: Mapping<(AccountId, u32), TokenId>,
+        /// Mapping from token to its index in the owner's token list.
+        owned_tokens_index: Mapping<TokenId, u32>,
+        /// Mapping from index to the token at that index of the list of all tokens.
+        all_tokens: Mapping<u32, TokenId>,
+        /// Mapping from token to its index in the list of all tokens.
+        all_tokens_index: Mapping<TokenId, u32>,
+        /// Number of tokens in existence.
+        total_supply: u32,
+        /// Lowest token ID that `mint_next` may assign.
+        next_token_id: TokenId,
+        /// Version of the storage layout, see `STORAGE_VERSION`.
+        storage_version: Lazy<u32, ManualKey<STORAGE_VERSION_KEY>>,
+        /// Account allowed to upgrade the contract, independently of the minter.
+        admin: Lazy<Option<AccountId>, ManualKey<ADMIN_KEY>>,
     }
 
     #[derive(Debug, PartialEq, Eq, Copy, Clone)]
@@ -28,6 +73,12 @@ pub mod erc721 {
         CannotInsert,
         CannotFetchValue,
         NotAllowed,
+        TransferRejected,
+        NotMinter,
+        NotPendingMinter,
+        SetCodeHashFailed,
+        StorageVersionTooNew,
+        NotAdmin,
     }
 
     /// Event emitted when a token transfer occurs.
@@ -52,11 +103,158 @@ pub mod erc721 {
         id: TokenId,
     }
 
+    /// Event emitted when an approval is revoked.
+    #[ink(event)]
+    pub struct Revoke {
+        #[ink(topic)]
+        from: AccountId,
+        #[ink(topic)]
+        to: AccountId,
+        #[ink(topic)]
+        id: TokenId,
+    }
+
+    /// Event emitted when the ownership of the contract changes.
+    #[ink(event)]
+    pub struct OwnershipTransferred {
+        #[ink(topic)]
+        previous_minter: Option<AccountId>,
+        #[ink(topic)]
+        new_minter: Option<AccountId>,
+    }
+
     impl Erc721 {
-        /// Creates a new ERC-721 token contract.
+        /// Creates a new ERC-721 token contract, owned and upgradable by the caller.
         #[ink(constructor)]
-        pub fn new() -> Self {
-            Default::default()
+        pub fn new(name: String, symbol: String) -> Self {
+            let caller = Self::env().caller();
+            let mut erc721 = Self {
+                minter: Some(caller),
+                name,
+                symbol,
+                ..Default::default()
+            };
+            erc721.storage_version.set(&STORAGE_VERSION);
+            erc721.admin.set(&Some(caller));
+            erc721
+        }
+
+        /// Returns the name of the collection.
+        #[ink(message)]
+        pub fn name(&self) -> String {
+            self.name.clone()
+        }
+
+        /// Returns the symbol of the collection.
+        #[ink(message)]
+        pub fn symbol(&self) -> String {
+            self.symbol.clone()
+        }
+
+        /// Returns the owner of the contract, allowed to mint tokens.
+        #[ink(message)]
+        pub fn minter(&self) -> Option<AccountId> {
+            self.minter
+        }
+
+        /// Returns the account the ownership is being transferred to, if any.
+        #[ink(message)]
+        pub fn pending_minter(&self) -> Option<AccountId> {
+            self.pending_minter
+        }
+
+        /// Proposes `new_minter` as the new owner of the contract.
+        ///
+        /// The ownership is only transferred once `new_minter` calls `accept_ownership`.
+        #[ink(message)]
+        pub fn transfer_ownership(&mut self, new_minter: AccountId) -> Result<(), Error> {
+            self.ensure_minter()?;
+            self.pending_minter = Some(new_minter);
+            Ok(())
+        }
+
+        /// Accepts a pending ownership transfer.
+        #[ink(message)]
+        pub fn accept_ownership(&mut self) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if self.pending_minter != Some(caller) {
+                return Err(Error::NotPendingMinter);
+            }
+
+            let previous_minter = self.minter;
+            self.minter = Some(caller);
+            self.pending_minter = None;
+
+            self.env().emit_event(OwnershipTransferred {
+                previous_minter,
+                new_minter: self.minter,
+            });
+            Ok(())
+        }
+
+        /// Gives up the ownership of the contract, after which no tokens can be minted.
+        ///
+        /// The contract can still be upgraded by the admin, see `set_admin`.
+        #[ink(message)]
+        pub fn renounce_ownership(&mut self) -> Result<(), Error> {
+            self.ensure_minter()?;
+
+            let previous_minter = self.minter;
+            self.minter = None;
+            self.pending_minter = None;
+
+            self.env().emit_event(OwnershipTransferred {
+                previous_minter,
+                new_minter: None,
+            });
+            Ok(())
+        }
+
+        /// Returns the account allowed to upgrade the contract, if any.
+        #[ink(message)]
+        pub fn admin(&self) -> Option<AccountId> {
+            self.admin.get().flatten()
+        }
+
+        /// Hands the upgrade rights over to `new_admin`, or gives them up for good with `None`.
+        #[ink(message)]
+        pub fn set_admin(&mut self, new_admin: Option<AccountId>) -> Result<(), Error> {
+            self.ensure_admin()?;
+            self.admin.set(&new_admin);
+            Ok(())
+        }
+
+        /// Replaces the code of the contract with the code at `code_hash`, keeping its storage.
+        ///
+        /// The new code takes effect from the next call; the admin should then call `migrate`
+        /// to bring the storage up to the layout the new code expects.
+        #[ink(message)]
+        pub fn set_code(&mut self, code_hash: Hash) -> Result<(), Error> {
+            self.ensure_admin()?;
+            self.env()
+                .set_code_hash(&code_hash)
+                .map_err(|_| Error::SetCodeHashFailed)
+        }
+
+        /// Returns the version of the storage layout.
+        #[ink(message)]
+        pub fn storage_version(&self) -> u32 {
+            self.storage_version.get().unwrap_or_default()
+        }
+
+        /// Migrates the storage written by an older code version to the current layout.
+        #[ink(message)]
+        pub fn migrate(&mut self) -> Result<(), Error> {
+            self.ensure_admin()?;
+            if self.storage_version() > STORAGE_VERSION {
+                return Err(Error::StorageVersionTooNew);
+            }
+
+            // Layout changes of each version go here, oldest first, e.g.
+            // `if self.storage_version() < 2 { ... }`.
+
+            self.storage_version.set(&STORAGE_VERSION);
+            Ok(())
         }
 
         /// Returns the balance of the owner.
@@ -73,6 +271,24 @@ pub mod erc721 {
             self.token_owner.get(id)
         }
 
+        /// Returns the number of tokens in existence.
+        #[ink(message)]
+        pub fn total_supply(&self) -> u32 {
+            self.total_supply
+        }
+
+        /// Returns the token at `index` of the list of all tokens.
+        #[ink(message)]
+        pub fn token_by_index(&self, index: u32) -> Option<TokenId> {
+            self.all_tokens.get(index)
+        }
+
+        /// Returns the token at `index` of the list of tokens owned by `owner`.
+        #[ink(message)]
+        pub fn owners_token_by_index(&self, owner: AccountId, index: u32) -> Option<TokenId> {
+            self.owned_tokens.get((owner, index))
+        }
+
         /// Returns the approved account ID for this token if any.
         #[ink(message)]
         pub fn get_approved(&self, id: TokenId) -> Option<AccountId> {
@@ -80,12 +296,35 @@ pub mod erc721 {
         }
 
         /// Approves the account to transfer the specified token on behalf of the caller.
+        ///
+        /// Replaces any existing approval for the token.
         #[ink(message)]
         pub fn approve(&mut self, to: AccountId, id: TokenId) -> Result<(), Error> {
             self.approve_for(&to, id)?;
             Ok(())
         }
 
+        /// Revokes the existing approval of the specified token.
+        #[ink(message)]
+        pub fn revoke(&mut self, id: TokenId) -> Result<(), Error> {
+            let caller = self.env().caller();
+            let owner = self.owner_of(id).ok_or(Error::TokenNotFound)?;
+            if owner != caller {
+                return Err(Error::NotAllowed);
+            };
+
+            let approved = self.token_approvals.get(id).ok_or(Error::NotApproved)?;
+            self.clear_approval(id);
+
+            self.env().emit_event(Revoke {
+                from: caller,
+                to: approved,
+                id,
+            });
+
+            Ok(())
+        }
+
         /// Transfers the token from the caller to the given destination.
         #[ink(message)]
         pub fn transfer(&mut self, destination: AccountId, id: TokenId) -> Result<(), Error> {
@@ -106,14 +345,94 @@ pub mod erc721 {
             Ok(())
         }
 
-        /// Creates a new token.
+        /// Creates a new token owned by the caller. Can only be called by the minter.
         #[ink(message)]
         pub fn mint(&mut self, id: TokenId) -> Result<(), Error> {
+            let caller = self.ensure_minter()?;
+            self.mint_token(&caller, id)
+        }
+
+        /// Creates a new token owned by `to`. Can only be called by the minter.
+        #[ink(message)]
+        pub fn mint_to(&mut self, to: AccountId, id: TokenId) -> Result<(), Error> {
+            self.ensure_minter()?;
+            self.mint_token(&to, id)
+        }
+
+        /// Creates a new token owned by `to` with the next free ID and returns that ID.
+        /// Can only be called by the minter.
+        #[ink(message)]
+        pub fn mint_next(&mut self, to: AccountId) -> Result<TokenId, Error> {
+            self.ensure_minter()?;
+
+            // Skip IDs that were already claimed through `mint`
+            let mut id = self.next_token_id;
+            while self.token_owner.contains(id) {
+                id = id.checked_add(1).ok_or(Error::CannotInsert)?;
+            }
+            self.next_token_id = id.checked_add(1).ok_or(Error::CannotInsert)?;
+
+            self.mint_token(&to, id)?;
+            Ok(id)
+        }
+
+        /// Creates a new token for each `(to, id)` pair. Can only be called by the minter.
+        ///
+        /// Fails without minting anything if any of the tokens cannot be minted.
+        #[ink(message)]
+        pub fn mint_batch_to(&mut self, tokens: Vec<(AccountId, TokenId)>) -> Result<(), Error> {
+            self.ensure_minter()?;
+            for (to, id) in tokens {
+                self.mint_token(&to, id)?;
+            }
+            Ok(())
+        }
+
+        /// Transfer approved or owned token, checking that a contract receiver accepts it.
+        ///
+        /// If `to` is a contract, its `on_erc721_received(operator, from, id, data)` message
+        /// is called and must return `ON_ERC721_RECEIVED`, otherwise the transfer is reverted.
+        #[ink(message)]
+        pub fn safe_transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            id: TokenId,
+            data: Vec<u8>,
+        ) -> Result<(), Error> {
+            self.transfer_token_from(&from, &to, id)?;
+            if self.env().is_contract(&to) {
+                self.check_on_erc721_received(&from, &to, id, data)?;
+            }
+            Ok(())
+        }
+
+        /// Deletes an existing token. Only the owner or an approved account can burn it.
+        #[ink(message)]
+        pub fn burn(&mut self, id: TokenId) -> Result<(), Error> {
             let caller = self.env().caller();
-            self.add_token_to(&caller, id)?;
+            let owner = self.owner_of(id).ok_or(Error::TokenNotFound)?;
+            if !self.approved_or_owner(caller, id, owner) {
+                return Err(Error::NotApproved);
+            };
+            self.clear_approval(id);
+            self.remove_token_from(&owner, id)?;
+            self.remove_token_from_all(id);
+            self.env().emit_event(Transfer {
+                from: Some(owner),
+                to: None,
+                id,
+            });
+            Ok(())
+        }
+
+        /// Creates token `id` owned by `to`.
+        fn mint_token(&mut self, to: &AccountId, id: TokenId) -> Result<(), Error> {
+            self.add_token_to(to, id)?;
+            self.add_token_to_all(id);
             self.env().emit_event(Transfer {
                 from: Some(AccountId::from([0x0; 32])),
-                to: Some(caller),
+                to: Some(*to),
                 id,
             });
             Ok(())
@@ -145,11 +464,41 @@ pub mod erc721 {
             Ok(())
         }
 
+        /// Calls `on_erc721_received` on the receiving contract `to`.
+        fn check_on_erc721_received(
+            &self,
+            from: &AccountId,
+            to: &AccountId,
+            id: TokenId,
+            data: Vec<u8>,
+        ) -> Result<(), Error> {
+            let result = build_call::<DefaultEnvironment>()
+                .call(*to)
+                .call_v1()
+                .gas_limit(0)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ON_ERC721_RECEIVED))
+                        .push_arg(self.env().caller())
+                        .push_arg(from)
+                        .push_arg(id)
+                        .push_arg(data),
+                )
+                .returns::<[u8; 4]>()
+                .try_invoke();
+
+            match result {
+                Ok(Ok(selector)) if selector == ON_ERC721_RECEIVED => Ok(()),
+                _ => Err(Error::TransferRejected),
+            }
+        }
+
         /// Removes token `id` from the owner.
         fn remove_token_from(&mut self, from: &AccountId, id: TokenId) -> Result<(), Error> {
             let Self {
                 token_owner,
                 owned_tokens_count,
+                owned_tokens,
+                owned_tokens_index,
                 ..
             } = self;
 
@@ -161,6 +510,19 @@ pub mod erc721 {
                 .get(from)
                 .map(|c| c.checked_sub(1).unwrap())
                 .ok_or(Error::CannotFetchValue)?;
+
+            // Move the last token of the owner into the slot of the removed token.
+            let index = owned_tokens_index.get(id).ok_or(Error::CannotFetchValue)?;
+            if index != count {
+                let last_id = owned_tokens
+                    .get((*from, count))
+                    .ok_or(Error::CannotFetchValue)?;
+                owned_tokens.insert((*from, index), &last_id);
+                owned_tokens_index.insert(last_id, &index);
+            }
+            owned_tokens.remove((*from, count));
+            owned_tokens_index.remove(id);
+
             owned_tokens_count.insert(from, &count);
             token_owner.remove(id);
 
@@ -172,6 +534,8 @@ pub mod erc721 {
             let Self {
                 token_owner,
                 owned_tokens_count,
+                owned_tokens,
+                owned_tokens_index,
                 ..
             } = self;
 
@@ -183,17 +547,41 @@ pub mod erc721 {
                 return Err(Error::NotAllowed);
             };
 
-            let count = owned_tokens_count
-                .get(to)
-                .map(|c| c.checked_add(1).unwrap())
-                .unwrap_or(1);
+            let index = owned_tokens_count.get(to).unwrap_or(0);
+            let count = index.checked_add(1).unwrap();
 
+            owned_tokens.insert((*to, index), &id);
+            owned_tokens_index.insert(id, &index);
             owned_tokens_count.insert(to, &count);
             token_owner.insert(id, to);
 
             Ok(())
         }
 
+        /// Appends token `id` to the list of all tokens.
+        fn add_token_to_all(&mut self, id: TokenId) {
+            let index = self.total_supply;
+            self.all_tokens.insert(index, &id);
+            self.all_tokens_index.insert(id, &index);
+            self.total_supply = index.checked_add(1).unwrap();
+        }
+
+        /// Removes token `id` from the list of all tokens.
+        fn remove_token_from_all(&mut self, id: TokenId) {
+            let last_index = self.total_supply.checked_sub(1).unwrap();
+            let index = self.all_tokens_index.get(id).unwrap();
+
+            // Move the last token into the slot of the removed token.
+            if index != last_index {
+                let last_id = self.all_tokens.get(last_index).unwrap();
+                self.all_tokens.insert(index, &last_id);
+                self.all_tokens_index.insert(last_id, &index);
+            }
+            self.all_tokens.remove(last_index);
+            self.all_tokens_index.remove(id);
+            self.total_supply = last_index;
+        }
+
         /// Approve the passed `AccountId` to transfer the specified token on behalf of
         /// the message's sender.
         fn approve_for(&mut self, to: &AccountId, id: TokenId) -> Result<(), Error> {
@@ -207,11 +595,7 @@ pub mod erc721 {
                 return Err(Error::NotAllowed);
             };
 
-            if self.token_approvals.contains(id) {
-                return Err(Error::CannotInsert);
-            } else {
-                self.token_approvals.insert(id, to);
-            }
+            self.token_approvals.insert(id, to);
 
             self.env().emit_event(Approval {
                 from: caller,
@@ -222,6 +606,24 @@ pub mod erc721 {
             Ok(())
         }
 
+        /// Returns the caller if it is the minter.
+        fn ensure_minter(&self) -> Result<AccountId, Error> {
+            let caller = self.env().caller();
+            if self.minter != Some(caller) {
+                return Err(Error::NotMinter);
+            }
+            Ok(caller)
+        }
+
+        /// Returns the caller if it is the admin.
+        fn ensure_admin(&self) -> Result<AccountId, Error> {
+            let caller = self.env().caller();
+            if self.admin() != Some(caller) {
+                return Err(Error::NotAdmin);
+            }
+            Ok(caller)
+        }
+
         /// Removes existing approval from token `id`.
         fn clear_approval(&mut self, id: TokenId) {
             self.token_approvals.remove(id);
@@ -239,4 +641,106 @@ pub mod erc721 {
                 && (from == owner || self.token_approvals.get(id) == Some(from))
         }
     }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn accounts() -> ink::env::test::DefaultAccounts<ink::env::DefaultEnvironment> {
+            ink::env::test::default_accounts::<ink::env::DefaultEnvironment>()
+        }
+
+        fn set_caller(caller: AccountId) {
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(caller);
+        }
+
+        /// Root of the storage as laid out at version 1. `Mapping` and `Lazy` fields live
+        /// under their own keys and take no space in the root.
+        #[ink::scale_derive(Encode, Decode)]
+        struct RootV1 {
+            minter: Option<AccountId>,
+            pending_minter: Option<AccountId>,
+            name: String,
+            symbol: String,
+            total_supply: u32,
+            next_token_id: TokenId,
+        }
+
+        #[ink::test]
+        fn migrate_loads_version_1_storage() {
+            let accounts = accounts();
+            // Tokens and approvals go to the mappings, which the root does not touch
+            let mut erc721 = Erc721::new(String::from("Collection"), String::from("COL"));
+            assert_eq!(erc721.mint(1), Ok(()));
+            assert_eq!(erc721.approve(accounts.bob, 1), Ok(()));
+
+            // Overwrite the root with the version 1 encoding and load it with this code
+            let root_key = <Erc721 as ink::storage::traits::StorageKey>::KEY;
+            let root = RootV1 {
+                minter: Some(accounts.alice),
+                pending_minter: None,
+                name: String::from("Collection"),
+                symbol: String::from("COL"),
+                total_supply: 1,
+                next_token_id: 2,
+            };
+            ink::env::set_contract_storage(&root_key, &root);
+            ink::env::set_contract_storage(&STORAGE_VERSION_KEY, &1u32);
+            let mut erc721: Erc721 = ink::env::get_contract_storage(&root_key)
+                .expect("root does not decode")
+                .expect("no root");
+            assert_eq!(erc721.storage_version(), 1);
+
+            assert_eq!(erc721.migrate(), Ok(()));
+            assert_eq!(erc721.storage_version(), STORAGE_VERSION);
+            assert_eq!(erc721.name(), String::from("Collection"));
+            assert_eq!(erc721.symbol(), String::from("COL"));
+            assert_eq!(erc721.minter(), Some(accounts.alice));
+            assert_eq!(erc721.owner_of(1), Some(accounts.alice));
+            assert_eq!(erc721.balance_of(accounts.alice), 1);
+            assert_eq!(erc721.get_approved(1), Some(accounts.bob));
+            assert_eq!(erc721.total_supply(), 1);
+        }
+
+        #[ink::test]
+        fn migrate_refuses_newer_storage() {
+            let mut erc721 = Erc721::new(String::from("Collection"), String::from("COL"));
+            erc721.storage_version.set(&(STORAGE_VERSION + 1));
+
+            assert_eq!(erc721.migrate(), Err(Error::StorageVersionTooNew));
+        }
+
+        #[ink::test]
+        fn upgrade_is_restricted_to_admin() {
+            let accounts = accounts();
+            let mut erc721 = Erc721::new(String::from("Collection"), String::from("COL"));
+
+            set_caller(accounts.bob);
+            assert_eq!(
+                erc721.set_code(Hash::from([0x01; 32])),
+                Err(Error::NotAdmin)
+            );
+            assert_eq!(erc721.migrate(), Err(Error::NotAdmin));
+            assert_eq!(erc721.set_admin(Some(accounts.bob)), Err(Error::NotAdmin));
+
+            set_caller(accounts.alice);
+            assert_eq!(erc721.set_admin(Some(accounts.bob)), Ok(()));
+            assert_eq!(erc721.admin(), Some(accounts.bob));
+            assert_eq!(erc721.migrate(), Err(Error::NotAdmin));
+
+            set_caller(accounts.bob);
+            assert_eq!(erc721.migrate(), Ok(()));
+        }
+
+        #[ink::test]
+        fn renounce_ownership_keeps_upgrades() {
+            let accounts = accounts();
+            let mut erc721 = Erc721::new(String::from("Collection"), String::from("COL"));
+            assert_eq!(erc721.renounce_ownership(), Ok(()));
+            assert_eq!(erc721.minter(), None);
+
+            assert_eq!(erc721.admin(), Some(accounts.alice));
+            assert_eq!(erc721.migrate(), Ok(()));
+        }
+    }
 }
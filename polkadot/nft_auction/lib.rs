@@ -6,7 +6,9 @@ mod nft_auction {
         call::{build_call, ExecutionInput, Selector},
         DefaultEnvironment,
     };
-    use ink::storage::Mapping;
+    use ink::storage::{traits::ManualKey, Lazy, Mapping};
+
+    use erc721::erc721::Error as Erc721Error;
 
     #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
     #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
@@ -19,6 +21,19 @@ mod nft_auction {
         BidTooLow,
         NotSeller,
         NotStarted,
+        SetCodeHashFailed,
+        StorageVersionTooNew,
+        /// The call to the NFT contract could not be made or did not decode.
+        NftCallFailed,
+        /// The NFT contract refused the transfer.
+        NftTransferFailed(Erc721Error),
+        /// The auction does not hold the NFT after taking custody of it.
+        NftNotOwned,
+        DurationTooShort,
+        DurationTooLong,
+        NotAdmin,
+        /// The code cannot be replaced between `start` and `end`.
+        AuctionInProgress,
     }
 
     #[ink(event)]
@@ -53,7 +68,33 @@ mod nft_auction {
     pub type TokenId = u32;
     pub type Result<T> = core::result::Result<T, Error>;
 
-    const DURATION: u64 = 5 * 60 * 1000; // in milliseconds
+    /// Snapshot of the auction state, as returned by `auction_info`.
+    #[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct AuctionInfo {
+        pub started: bool,
+        pub ended: bool,
+        pub seller: AccountId,
+        pub end_at: Option<Timestamp>,
+        pub highest_bidder: Option<AccountId>,
+        pub highest_bid: Balance,
+        pub nft: AccountId,
+        pub nft_id: TokenId,
+        pub duration: u64,
+    }
+
+    const DEFAULT_DURATION: u64 = 5 * 60 * 1000; // in milliseconds
+    const MIN_DURATION: u64 = 60 * 1000; // in milliseconds
+    const MAX_DURATION: u64 = 30 * 24 * 60 * 60 * 1000; // in milliseconds
+
+    /// Version of the storage layout written by this code; version 2 adds the duration and
+    /// the admin, both under their own key so that the version 1 root still decodes.
+    pub const STORAGE_VERSION: u32 = 2;
+
+    /// Storage key of the layout version, fixed so that every code version finds it.
+    const STORAGE_VERSION_KEY: u32 = 0x7665_7273;
+    /// Storage key of the upgrade admin.
+    const ADMIN_KEY: u32 = 0x6164_6d6e;
 
     #[ink(storage)]
     pub struct NftAuction {
@@ -66,12 +107,29 @@ mod nft_auction {
         bids: Mapping<AccountId, Balance>,
         nft: AccountId,
         nft_id: TokenId,
+        storage_version: Lazy<u32, ManualKey<STORAGE_VERSION_KEY>>,
+        duration: Lazy<u64>,
+        admin: Lazy<Option<AccountId>, ManualKey<ADMIN_KEY>>,
     }
 
     impl NftAuction {
+        /// Creates an auction of `nft_id`, with bidding open for `duration` milliseconds
+        /// once started.
         #[ink(constructor)]
-        pub fn new(nft: AccountId, nft_id: TokenId, starting_bid: Balance) -> Self {
-            Self {
+        pub fn new(
+            nft: AccountId,
+            nft_id: TokenId,
+            starting_bid: Balance,
+            duration: u64,
+        ) -> Result<Self> {
+            if duration < MIN_DURATION {
+                return Err(Error::DurationTooShort);
+            }
+            if duration > MAX_DURATION {
+                return Err(Error::DurationTooLong);
+            }
+
+            let mut auction = Self {
                 started: false,
                 ended: false,
                 seller: Self::env().caller(),
@@ -81,12 +139,24 @@ mod nft_auction {
                 bids: Mapping::default(),
                 nft,
                 nft_id,
-            }
+                storage_version: Lazy::default(),
+                duration: Lazy::default(),
+                admin: Lazy::default(),
+            };
+            auction.storage_version.set(&STORAGE_VERSION);
+            auction.duration.set(&duration);
+            auction.admin.set(&Some(Self::env().caller()));
+            Ok(auction)
         }
 
         #[ink(message)]
-        #[allow(clippy::arithmetic_side_effects)]
         pub fn start(&mut self) -> Result<()> {
+            let nft = NftRef(self.nft);
+            self.start_with(&nft)
+        }
+
+        #[allow(clippy::arithmetic_side_effects)]
+        fn start_with(&mut self, nft: &impl NftContract) -> Result<()> {
             let caller = self.env().caller();
             if self.started {
                 return Err(Error::AlreadyStarted);
@@ -95,24 +165,15 @@ mod nft_auction {
                 return Err(Error::NotSeller);
             }
 
-            // https://use.ink/basics/cross-contract-calling/
-            // https://docs.alephzero.org/aleph-zero/build/cross-contract-calls/using-dynamic-calls
-            let _my_return_value = build_call::<DefaultEnvironment>()
-                .call(self.nft) //Contract address
-                .call_v1()
-                .gas_limit(0)
-                .exec_input(
-                    ExecutionInput::new(Selector::new(ink::selector_bytes!("transfer_from")))
-                        .push_arg(self.seller) //sender
-                        .push_arg(self.env().account_id()) //address(this)
-                        .push_arg(self.nft_id), //nftId
-                )
-                .returns::<ink::MessageResult<()>>()
-                .invoke();
+            let this = self.env().account_id();
+            self.transfer_nft(nft, self.seller, this)?;
+            if self.nft_owner(nft)? != Some(this) {
+                return Err(Error::NftNotOwned);
+            }
 
             self.started = true;
             let now = self.env().block_timestamp();
-            self.end_at = Some(now + DURATION);
+            self.end_at = Some(now + self.duration());
 
             self.env().emit_event(Start {
                 caller,
@@ -139,12 +200,10 @@ mod nft_auction {
                 return Err(Error::BidTooLow);
             }
 
-            // A previous bidder should best withdraw before bidding again
+            // The outbid highest bid becomes withdrawable by its bidder
             if let Some(b) = self.highest_bidder {
-                self.bids.insert(
-                    b,
-                    &(self.bids.get(b).unwrap_or(0) + self.env().transferred_value()),
-                );
+                self.bids
+                    .insert(b, &(self.bids.get(b).unwrap_or(0) + self.highest_bid));
             }
 
             self.highest_bidder = Some(caller);
@@ -183,6 +242,11 @@ mod nft_auction {
 
         #[ink(message)]
         pub fn end(&mut self) -> Result<()> {
+            let nft = NftRef(self.nft);
+            self.end_with(&nft)
+        }
+
+        fn end_with(&mut self, nft: &impl NftContract) -> Result<()> {
             if !self.started {
                 return Err(Error::NotStarted);
             }
@@ -199,20 +263,7 @@ mod nft_auction {
             match self.highest_bidder {
                 Some(b) => {
                     // Send NFT to bidder and bid to seller
-                    let _my_return_value = build_call::<DefaultEnvironment>()
-                        .call(self.nft) //Contract address
-                        .call_v1()
-                        .gas_limit(0)
-                        .exec_input(
-                            ExecutionInput::new(Selector::new(ink::selector_bytes!(
-                                "transfer_from"
-                            )))
-                            .push_arg(self.env().account_id())
-                            .push_arg(b) //highest bidder
-                            .push_arg(self.nft_id),
-                        )
-                        .returns::<ink::MessageResult<()>>()
-                        .invoke();
+                    self.transfer_nft(nft, self.env().account_id(), b)?;
 
                     if self.env().transfer(self.seller, self.highest_bid).is_err() {
                         panic!(
@@ -221,25 +272,10 @@ mod nft_auction {
                              contract's balance below minimum balance."
                         )
                     }
-
-                    return Err(Error::BidTooLow);
                 }
                 None => {
                     // Send NFT back to seller
-                    let _my_return_value = build_call::<DefaultEnvironment>()
-                        .call(self.nft)
-                        .call_v1()
-                        .gas_limit(0)
-                        .exec_input(
-                            ExecutionInput::new(Selector::new(ink::selector_bytes!(
-                                "transfer_from"
-                            )))
-                            .push_arg(self.env().account_id())
-                            .push_arg(self.seller)
-                            .push_arg(self.nft_id),
-                        )
-                        .returns::<ink::MessageResult<()>>()
-                        .invoke();
+                    self.transfer_nft(nft, self.env().account_id(), self.seller)?;
                 }
             }
 
@@ -251,5 +287,541 @@ mod nft_auction {
 
             Ok(())
         }
+
+        /// Returns whether the auction has been started by the seller.
+        #[ink(message)]
+        pub fn started(&self) -> bool {
+            self.started
+        }
+
+        /// Returns whether the auction has been settled.
+        #[ink(message)]
+        pub fn ended(&self) -> bool {
+            self.ended
+        }
+
+        /// Returns the seller of the NFT.
+        #[ink(message)]
+        pub fn seller(&self) -> AccountId {
+            self.seller
+        }
+
+        /// Returns the timestamp at which bidding ends, if the auction has started.
+        #[ink(message)]
+        pub fn end_at(&self) -> Option<Timestamp> {
+            self.end_at
+        }
+
+        /// Returns how long bidding stays open once the auction starts, in milliseconds.
+        #[ink(message)]
+        pub fn duration(&self) -> u64 {
+            self.duration.get().unwrap_or(DEFAULT_DURATION)
+        }
+
+        /// Returns the current highest bidder, if any.
+        #[ink(message)]
+        pub fn highest_bidder(&self) -> Option<AccountId> {
+            self.highest_bidder
+        }
+
+        /// Returns the current highest bid, or the starting bid if nobody has bid yet.
+        #[ink(message)]
+        pub fn highest_bid(&self) -> Balance {
+            self.highest_bid
+        }
+
+        /// Returns the amount `bidder` can withdraw.
+        #[ink(message)]
+        pub fn bid_of(&self, bidder: AccountId) -> Balance {
+            self.bids.get(bidder).unwrap_or(0)
+        }
+
+        /// Returns the NFT contract and the ID of the auctioned token.
+        #[ink(message)]
+        pub fn nft(&self) -> (AccountId, TokenId) {
+            (self.nft, self.nft_id)
+        }
+
+        /// Returns the complete auction state in a single call.
+        #[ink(message)]
+        pub fn auction_info(&self) -> AuctionInfo {
+            AuctionInfo {
+                started: self.started,
+                ended: self.ended,
+                seller: self.seller,
+                end_at: self.end_at,
+                highest_bidder: self.highest_bidder,
+                highest_bid: self.highest_bid,
+                nft: self.nft,
+                nft_id: self.nft_id,
+                duration: self.duration(),
+            }
+        }
+
+        /// Returns the account allowed to upgrade the contract, if any.
+        #[ink(message)]
+        pub fn admin(&self) -> Option<AccountId> {
+            // Version 1 storage has no admin yet; its upgrades stay with the seller
+            self.admin.get().unwrap_or(Some(self.seller))
+        }
+
+        /// Hands the upgrade rights to `new_admin`, or gives them up for good with `None`.
+        #[ink(message)]
+        pub fn set_admin(&mut self, new_admin: Option<AccountId>) -> Result<()> {
+            self.ensure_admin()?;
+            self.admin.set(&new_admin);
+            Ok(())
+        }
+
+        /// Replaces the code of the contract with the code at `code_hash`, keeping its storage.
+        ///
+        /// The code cannot change while bidding is open, so that the escrowed bids stay under
+        /// the rules they were placed with. The new code takes effect from the next call; the
+        /// admin should then call `migrate` to update the storage layout.
+        #[ink(message)]
+        pub fn set_code(&mut self, code_hash: Hash) -> Result<()> {
+            self.ensure_admin()?;
+            if self.started && !self.ended {
+                return Err(Error::AuctionInProgress);
+            }
+            self.env()
+                .set_code_hash(&code_hash)
+                .map_err(|_| Error::SetCodeHashFailed)
+        }
+
+        /// Returns the version of the storage layout, 0 if it was written before versions
+        /// were recorded.
+        #[ink(message)]
+        pub fn storage_version(&self) -> u32 {
+            self.storage_version.get().unwrap_or_default()
+        }
+
+        /// Migrates the storage written by an older code version to the current layout.
+        #[ink(message)]
+        pub fn migrate(&mut self) -> Result<()> {
+            self.ensure_admin()?;
+            let storage_version = self.storage_version();
+            if storage_version > STORAGE_VERSION {
+                return Err(Error::StorageVersionTooNew);
+            }
+
+            // Layout changes of each version go here, oldest first
+            if storage_version < 2 {
+                self.duration.set(&DEFAULT_DURATION);
+                self.admin.set(&Some(self.seller));
+            }
+
+            self.storage_version.set(&STORAGE_VERSION);
+            Ok(())
+        }
+
+        /// Returns the caller if it is the admin.
+        fn ensure_admin(&self) -> Result<AccountId> {
+            let caller = self.env().caller();
+            if self.admin() != Some(caller) {
+                return Err(Error::NotAdmin);
+            }
+            Ok(caller)
+        }
+
+        /// Transfers the auctioned NFT from `from` to `to` through `nft`.
+        fn transfer_nft(
+            &self,
+            nft: &impl NftContract,
+            from: AccountId,
+            to: AccountId,
+        ) -> Result<()> {
+            nft.transfer_from(from, to, self.nft_id)
+                .map_err(|_| Error::NftCallFailed)?
+                .map_err(|_| Error::NftCallFailed)?
+                .map_err(Error::NftTransferFailed)
+        }
+
+        /// Returns the current owner of the auctioned NFT, according to `nft`.
+        fn nft_owner(&self, nft: &impl NftContract) -> Result<Option<AccountId>> {
+            nft.owner_of(self.nft_id)
+                .map_err(|_| Error::NftCallFailed)?
+                .map_err(|_| Error::NftCallFailed)
+        }
+    }
+
+    /// Outcome of a call to another contract: whether the call went through, whether the
+    /// callee could dispatch it, and then the value the message returned.
+    type CallResult<T> = ink::env::Result<ink::MessageResult<T>>;
+
+    /// The calls the auction makes to the NFT contract. Messages go through `NftRef`; unit
+    /// tests stand in for it, as the off-chain environment cannot call other contracts.
+    trait NftContract {
+        fn transfer_from(
+            &self,
+            from: AccountId,
+            to: AccountId,
+            id: TokenId,
+        ) -> CallResult<core::result::Result<(), Erc721Error>>;
+
+        fn owner_of(&self, id: TokenId) -> CallResult<Option<AccountId>>;
+    }
+
+    /// The deployed NFT contract at the given account.
+    // https://use.ink/basics/cross-contract-calling/
+    // https://docs.alephzero.org/aleph-zero/build/cross-contract-calls/using-dynamic-calls
+    struct NftRef(AccountId);
+
+    impl NftContract for NftRef {
+        fn transfer_from(
+            &self,
+            from: AccountId,
+            to: AccountId,
+            id: TokenId,
+        ) -> CallResult<core::result::Result<(), Erc721Error>> {
+            build_call::<DefaultEnvironment>()
+                .call(self.0)
+                .call_v1()
+                .gas_limit(0)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ink::selector_bytes!("transfer_from")))
+                        .push_arg(from)
+                        .push_arg(to)
+                        .push_arg(id),
+                )
+                .returns::<core::result::Result<(), Erc721Error>>()
+                .try_invoke()
+        }
+
+        fn owner_of(&self, id: TokenId) -> CallResult<Option<AccountId>> {
+            build_call::<DefaultEnvironment>()
+                .call(self.0)
+                .call_v1()
+                .gas_limit(0)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ink::selector_bytes!("owner_of")))
+                        .push_arg(id),
+                )
+                .returns::<Option<AccountId>>()
+                .try_invoke()
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        use ink::env::DefaultEnvironment;
+
+        fn accounts() -> ink::env::test::DefaultAccounts<DefaultEnvironment> {
+            ink::env::test::default_accounts::<DefaultEnvironment>()
+        }
+
+        fn set_caller(caller: AccountId) {
+            ink::env::test::set_caller::<DefaultEnvironment>(caller);
+        }
+
+        fn balance_of(account: AccountId) -> Balance {
+            ink::env::test::get_account_balance::<DefaultEnvironment>(account).unwrap_or(0)
+        }
+
+        fn set_balance(account: AccountId, balance: Balance) {
+            ink::env::test::set_account_balance::<DefaultEnvironment>(account, balance);
+        }
+
+        /// Smallest balance the off-chain engine lets an account hold, other than 0.
+        const EXISTENTIAL_DEPOSIT: Balance = 1_000_000;
+        const UNIT: Balance = 1_000_000_000_000;
+
+        /// Stands in for the NFT contract, which the off-chain environment cannot call.
+        enum MockNft {
+            /// Accepts every transfer and reports `owner` as the owner of the token.
+            Accepts { owner: AccountId },
+            /// Refuses every transfer with the given error.
+            Refuses(Erc721Error),
+            /// Traps on every call.
+            Traps,
+        }
+
+        impl MockNft {
+            /// An NFT contract that hands the token over to the auction.
+            fn accepting() -> Self {
+                MockNft::Accepts {
+                    owner: ink::env::test::callee::<DefaultEnvironment>(),
+                }
+            }
+        }
+
+        impl NftContract for MockNft {
+            fn transfer_from(
+                &self,
+                _from: AccountId,
+                _to: AccountId,
+                _id: TokenId,
+            ) -> CallResult<core::result::Result<(), Erc721Error>> {
+                match self {
+                    MockNft::Accepts { .. } => Ok(Ok(Ok(()))),
+                    MockNft::Refuses(error) => Ok(Ok(Err(*error))),
+                    MockNft::Traps => Err(ink::env::Error::CalleeTrapped),
+                }
+            }
+
+            fn owner_of(&self, _id: TokenId) -> CallResult<Option<AccountId>> {
+                match self {
+                    MockNft::Accepts { owner } => Ok(Ok(Some(*owner))),
+                    MockNft::Refuses(_) | MockNft::Traps => Err(ink::env::Error::CalleeTrapped),
+                }
+            }
+        }
+
+        /// Deploys the auction at its own account, holding only the existential deposit. The
+        /// seller and the bidders get 100 units each.
+        fn deploy_auction(starting_bid: Balance) -> NftAuction {
+            let accounts = accounts();
+            ink::env::test::set_callee::<DefaultEnvironment>(accounts.frank);
+            set_balance(accounts.frank, EXISTENTIAL_DEPOSIT);
+            for account in [accounts.alice, accounts.bob, accounts.charlie] {
+                set_balance(account, 100 * UNIT);
+            }
+            set_caller(accounts.alice);
+
+            NftAuction::new(accounts.eve, 7, starting_bid, MIN_DURATION).unwrap()
+        }
+
+        /// Deploys the auction and starts it, taking custody of the NFT.
+        fn start_auction(starting_bid: Balance) -> NftAuction {
+            let mut auction = deploy_auction(starting_bid);
+            assert_eq!(auction.start_with(&MockNft::accepting()), Ok(()));
+            auction
+        }
+
+        /// Bids `value` as `bidder`, moving the value from the bidder to the auction.
+        fn bid(auction: &mut NftAuction, bidder: AccountId, value: Balance) -> Result<()> {
+            let contract = ink::env::test::callee::<DefaultEnvironment>();
+            set_caller(bidder);
+            ink::env::test::set_value_transferred::<DefaultEnvironment>(value);
+            set_balance(bidder, balance_of(bidder) - value);
+            set_balance(contract, balance_of(contract) + value);
+            let result = auction.bid();
+            ink::env::test::set_value_transferred::<DefaultEnvironment>(0);
+            result
+        }
+
+        fn end_bidding(auction: &NftAuction) {
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(auction.end_at().unwrap());
+        }
+
+        #[ink::test]
+        fn outbid_bidder_is_credited_its_own_bid() {
+            let accounts = accounts();
+            let mut auction = start_auction(UNIT);
+
+            assert_eq!(bid(&mut auction, accounts.bob, 10 * UNIT), Ok(()));
+            assert_eq!(bid(&mut auction, accounts.charlie, 25 * UNIT), Ok(()));
+
+            assert_eq!(auction.bid_of(accounts.bob), 10 * UNIT);
+            assert_eq!(auction.bid_of(accounts.charlie), 0);
+            assert_eq!(auction.highest_bidder(), Some(accounts.charlie));
+            assert_eq!(auction.highest_bid(), 25 * UNIT);
+        }
+
+        #[ink::test]
+        fn end_settles_successfully() {
+            let accounts = accounts();
+            let mut auction = start_auction(UNIT);
+            assert_eq!(bid(&mut auction, accounts.bob, 10 * UNIT), Ok(()));
+            let seller_balance = balance_of(accounts.alice);
+
+            end_bidding(&auction);
+            assert_eq!(auction.end_with(&MockNft::accepting()), Ok(()));
+            assert!(auction.ended());
+            assert_eq!(balance_of(accounts.alice), seller_balance + 10 * UNIT);
+            assert_eq!(balance_of(accounts.frank), EXISTENTIAL_DEPOSIT);
+            assert_eq!(
+                auction.end_with(&MockNft::accepting()),
+                Err(Error::AlreadyEnded)
+            );
+        }
+
+        #[ink::test]
+        fn balance_is_conserved_across_settlement() {
+            let accounts = accounts();
+            let contract = ink::env::test::callee::<DefaultEnvironment>();
+            let mut auction = start_auction(UNIT);
+
+            // Bob is outbid by Charlie, who is outbid by Bob again
+            assert_eq!(bid(&mut auction, accounts.bob, 10 * UNIT), Ok(()));
+            assert_eq!(bid(&mut auction, accounts.charlie, 20 * UNIT), Ok(()));
+            assert_eq!(bid(&mut auction, accounts.bob, 30 * UNIT), Ok(()));
+            let total_bids = (10 + 20 + 30) * UNIT;
+            assert_eq!(balance_of(contract), EXISTENTIAL_DEPOSIT + total_bids);
+
+            let payees = [accounts.alice, accounts.bob, accounts.charlie];
+            let balances_before = payees.map(balance_of);
+
+            set_caller(accounts.charlie);
+            assert_eq!(auction.withdraw(), Ok(()));
+            assert_eq!(auction.bid_of(accounts.charlie), 0);
+
+            end_bidding(&auction);
+            set_caller(accounts.django);
+            assert_eq!(auction.end_with(&MockNft::accepting()), Ok(()));
+
+            set_caller(accounts.bob);
+            assert_eq!(auction.withdraw(), Ok(()));
+            assert_eq!(auction.bid_of(accounts.bob), 0);
+
+            // The seller got the winning 30 and both outbid bids were paid back
+            let payouts = [0, 1, 2].map(|i| balance_of(payees[i]) - balances_before[i]);
+            assert_eq!(payouts, [30 * UNIT, 10 * UNIT, 20 * UNIT]);
+            assert_eq!(
+                balance_of(contract) + payouts.iter().sum::<Balance>(),
+                EXISTENTIAL_DEPOSIT + total_bids
+            );
+            assert_eq!(balance_of(contract), EXISTENTIAL_DEPOSIT);
+        }
+
+        #[ink::test]
+        fn start_fails_if_the_nft_transfer_is_refused() {
+            let mut auction = deploy_auction(UNIT);
+
+            assert_eq!(
+                auction.start_with(&MockNft::Refuses(Erc721Error::NotApproved)),
+                Err(Error::NftTransferFailed(Erc721Error::NotApproved))
+            );
+            assert!(!auction.started());
+        }
+
+        #[ink::test]
+        fn start_fails_if_the_nft_contract_cannot_be_called() {
+            let mut auction = deploy_auction(UNIT);
+
+            assert_eq!(
+                auction.start_with(&MockNft::Traps),
+                Err(Error::NftCallFailed)
+            );
+            assert!(!auction.started());
+        }
+
+        #[ink::test]
+        fn start_fails_if_the_auction_does_not_hold_the_nft() {
+            let accounts = accounts();
+            let mut auction = deploy_auction(UNIT);
+
+            // The transfer reports success but the seller still owns the token
+            let nft = MockNft::Accepts {
+                owner: accounts.alice,
+            };
+            assert_eq!(auction.start_with(&nft), Err(Error::NftNotOwned));
+            assert!(!auction.started());
+        }
+
+        #[ink::test]
+        fn end_fails_if_the_nft_transfer_is_refused() {
+            let accounts = accounts();
+            let mut auction = start_auction(UNIT);
+            assert_eq!(bid(&mut auction, accounts.bob, 10 * UNIT), Ok(()));
+            let seller_balance = balance_of(accounts.alice);
+
+            // The seller is not paid when the winner does not get the NFT
+            end_bidding(&auction);
+            assert_eq!(
+                auction.end_with(&MockNft::Refuses(Erc721Error::NotOwner)),
+                Err(Error::NftTransferFailed(Erc721Error::NotOwner))
+            );
+            assert_eq!(balance_of(accounts.alice), seller_balance);
+        }
+
+        /// Root of the storage as laid out at version 1. The `Mapping` field lives under its
+        /// own keys and takes no space in the root.
+        #[derive(scale::Encode, scale::Decode)]
+        struct RootV1 {
+            started: bool,
+            ended: bool,
+            seller: AccountId,
+            end_at: Option<Timestamp>,
+            highest_bidder: Option<AccountId>,
+            highest_bid: Balance,
+            nft: AccountId,
+            nft_id: TokenId,
+        }
+
+        /// Writes `root` as version 1 code did, then loads the contract from the root key as
+        /// the current code does on every call.
+        fn load_version_1(root: RootV1) -> NftAuction {
+            let root_key = <NftAuction as ink::storage::traits::StorageKey>::KEY;
+            ink::env::set_contract_storage(&root_key, &root);
+            ink::env::set_contract_storage(&STORAGE_VERSION_KEY, &1u32);
+            ink::env::get_contract_storage(&root_key)
+                .expect("root does not decode")
+                .expect("no root")
+        }
+
+        #[ink::test]
+        fn migrate_loads_version_1_storage() {
+            let accounts = accounts();
+            let mut auction = load_version_1(RootV1 {
+                started: false,
+                ended: false,
+                seller: accounts.bob,
+                end_at: None,
+                highest_bidder: None,
+                highest_bid: 100,
+                nft: accounts.eve,
+                nft_id: 7,
+            });
+            assert_eq!(auction.storage_version(), 1);
+            assert_eq!(auction.admin(), Some(accounts.bob));
+
+            assert_eq!(auction.migrate(), Err(Error::NotAdmin));
+            set_caller(accounts.bob);
+            assert_eq!(auction.migrate(), Ok(()));
+
+            assert_eq!(auction.storage_version(), STORAGE_VERSION);
+            assert_eq!(auction.duration.get(), Some(DEFAULT_DURATION));
+            assert_eq!(auction.admin.get(), Some(Some(accounts.bob)));
+            assert_eq!(auction.seller(), accounts.bob);
+            assert_eq!(auction.nft(), (accounts.eve, 7));
+            assert_eq!(auction.highest_bid(), 100);
+            assert!(!auction.started());
+        }
+
+        #[ink::test]
+        fn migrate_refuses_newer_storage() {
+            let accounts = accounts();
+            let mut auction = NftAuction::new(accounts.eve, 7, 100, MIN_DURATION).unwrap();
+            auction.storage_version.set(&(STORAGE_VERSION + 1));
+
+            assert_eq!(auction.migrate(), Err(Error::StorageVersionTooNew));
+        }
+
+        #[ink::test]
+        fn upgrade_is_restricted_to_admin() {
+            let accounts = accounts();
+            let mut auction = NftAuction::new(accounts.eve, 7, 100, MIN_DURATION).unwrap();
+            assert_eq!(auction.admin(), Some(accounts.alice));
+
+            set_caller(accounts.bob);
+            assert_eq!(
+                auction.set_code(Hash::from([0x01; 32])),
+                Err(Error::NotAdmin)
+            );
+            assert_eq!(auction.migrate(), Err(Error::NotAdmin));
+            assert_eq!(auction.set_admin(Some(accounts.bob)), Err(Error::NotAdmin));
+
+            set_caller(accounts.alice);
+            assert_eq!(auction.set_admin(Some(accounts.bob)), Ok(()));
+            assert_eq!(auction.migrate(), Err(Error::NotAdmin));
+
+            set_caller(accounts.bob);
+            assert_eq!(auction.migrate(), Ok(()));
+        }
+
+        #[ink::test]
+        fn set_code_is_refused_while_bidding_is_open() {
+            let accounts = accounts();
+            let mut auction = NftAuction::new(accounts.eve, 7, 100, MIN_DURATION).unwrap();
+            auction.started = true;
+
+            assert_eq!(
+                auction.set_code(Hash::from([0x01; 32])),
+                Err(Error::AuctionInProgress)
+            );
+        }
     }
 }